@@ -0,0 +1,50 @@
+use super::Point;
+
+///
+/// A color with red, green, blue, and alpha components from 0 to 255
+///
+/// An alpha of 255 is opaque, and an alpha of 0 is transparent.
+///
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Color {
+    /// Creates an opaque color
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Color {
+        Color { red, green, blue, alpha: 255 }
+    }
+    /// Creates a color that may be partly transparent
+    pub fn rgba(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color { red, green, blue, alpha }
+    }
+}
+
+impl Default for Color {
+    /// Returns opaque black
+    fn default() -> Self {
+        Color::rgb(0, 0, 0)
+    }
+}
+
+///
+/// Something that layers can draw on
+///
+/// Drawing backends implement this trait. All points are in display coordinates, the same
+/// coordinates that the projection passed to Layer::draw produces. Each drawing operation uses
+/// the color most recently set with set_color.
+///
+pub trait Canvas {
+    /// Sets the color used by the following drawing operations
+    fn set_color(&mut self, color: Color);
+    /// Draws a single point
+    fn draw_point(&mut self, point: &Point<f64>);
+    /// Draws a straight line between two points
+    fn draw_line(&mut self, start: &Point<f64>, end: &Point<f64>);
+    /// Fills the area inside a ring of points, which is closed from the last point back to the first
+    fn fill_polygon(&mut self, points: &[Point<f64>]);
+}
@@ -0,0 +1,126 @@
+use super::{LatLonRect, Point};
+use projection::Projection;
+use canvas::Canvas;
+use layer::{self, Layer};
+
+/// A function that draws a north arrow, given its position and angle
+type DrawArrow = Box<dyn Fn(&Point<f64>, f64)>;
+
+///
+/// A layer that shows which way north is
+///
+/// The layer does not draw anything itself. Each time it is drawn, it finds the direction of
+/// true north at its anchor and calls its callback with the anchor's display coordinates and
+/// the angle that a north arrow there should point in. The direction of north can change
+/// whenever the view changes, so it is calculated again for every draw.
+///
+pub struct CompassLayer {
+    /// The position of the arrow, as fractions of the viewport width and height
+    anchor: Point<f64>,
+    /// The function that draws the arrow
+    draw_arrow: DrawArrow,
+}
+
+impl CompassLayer {
+    ///
+    /// Creates a compass layer
+    ///
+    /// anchor is the position of the arrow in display coordinates, as fractions of the viewport
+    /// width and height. For example, (0.9, 0.9) is near the upper right corner when the
+    /// origin of display coordinates is at the lower left corner.
+    ///
+    /// draw_arrow is called with the display coordinates of the anchor and the angle of north,
+    /// in radians counterclockwise from the display X axis. It is not called if the direction of
+    /// north cannot be found at the anchor, for example at a pole or outside the globe.
+    ///
+    pub fn new<F>(anchor: Point<f64>, draw_arrow: F) -> CompassLayer where F: 'static + Fn(&Point<f64>, f64) {
+        CompassLayer {
+            anchor,
+            draw_arrow: Box::new(draw_arrow),
+        }
+    }
+
+    /// Returns the anchor, as fractions of the viewport width and height
+    pub fn anchor(&self) -> Point<f64> {
+        self.anchor.clone()
+    }
+    /// Sets the anchor, as fractions of the viewport width and height
+    pub fn set_anchor(&mut self, anchor: Point<f64>) {
+        self.anchor = anchor
+    }
+}
+
+impl Layer for CompassLayer {
+    fn draw(&self, _canvas: &mut dyn Canvas, projection: &dyn Projection, _x: i32, _y: i32, width: i32, height: i32) {
+        let pixel = Point { x: self.anchor.x * width as f64, y: self.anchor.y * height as f64 };
+        let position = match projection.try_unproject(&pixel) {
+            Ok(position) => position,
+            Err(_) => return,
+        };
+        let north = layer::geographic_direction(projection, &position, 0.0);
+        if north.x.is_finite() && north.y.is_finite() {
+            (self.draw_arrow)(&pixel, f64::atan2(north.y, north.x));
+        }
+    }
+
+    fn bounds(&self) -> Option<LatLonRect> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::f64::consts::PI;
+    use std::rc::Rc;
+    use super::*;
+    use equirectangular::EquirectangularProjection;
+    use mock::MockCanvas;
+    use ::{Latitude, Longitude, LatLon, close_enough};
+
+    /// An equirectangular projection, rotated so that a bearing is up
+    struct Rotated {
+        bearing_degrees: f64,
+    }
+
+    impl Projection for Rotated {
+        fn project(&self, position: &LatLon) -> Point<f64> {
+            let unrotated = EquirectangularProjection.project(position);
+            let (sin, cos) = self.bearing_degrees.to_radians().sin_cos();
+            Point { x: unrotated.x * cos - unrotated.y * sin, y: unrotated.x * sin + unrotated.y * cos }
+        }
+        fn unproject(&self, position: &Point<f64>) -> LatLon {
+            let (sin, cos) = self.bearing_degrees.to_radians().sin_cos();
+            let x = position.x * cos + position.y * sin;
+            let y = position.y * cos - position.x * sin;
+            LatLon { latitude: Latitude(y), longitude: Longitude(x) }
+        }
+    }
+
+    fn arrow_angle(projection: &dyn Projection) -> Option<f64> {
+        let angle = Rc::new(Cell::new(None));
+        let drawn = angle.clone();
+        let compass = CompassLayer::new(Point { x: 0.1, y: 0.1 }, move |anchor, angle| {
+            assert_eq!(&Point { x: 10.0, y: 5.0 }, anchor);
+            drawn.set(Some(angle));
+        });
+        compass.draw(&mut MockCanvas::new(), projection, 0, 0, 100, 50);
+        angle.get()
+    }
+
+    #[test]
+    fn test_compass_up() {
+        let angle = arrow_angle(&Rotated { bearing_degrees: 0.0 }).unwrap();
+        assert!(close_enough(PI / 2.0, angle));
+    }
+    #[test]
+    fn test_compass_rotated() {
+        // With east up, north is to the left
+        let angle = arrow_angle(&Rotated { bearing_degrees: 90.0 }).unwrap();
+        assert!(close_enough(PI, angle.abs()));
+    }
+    #[test]
+    fn test_compass_no_bounds() {
+        assert_eq!(None, CompassLayer::new(Point::origin(), |_, _| ()).bounds());
+    }
+}
@@ -10,7 +10,7 @@ impl Projection for EquirectangularProjection {
     fn project(&self, position: &LatLon) -> Point<f64> {
         let x: f64 = position.longitude.into();
         let y: f64 = position.latitude.into();
-        Point { x: x, y: y }
+        Point { x, y }
     }
 
     fn unproject(&self, position: &Point<f64>) -> LatLon {
@@ -19,4 +19,8 @@ impl Projection for EquirectangularProjection {
             longitude: Longitude(position.x),
         }
     }
+
+    fn horizontal_period(&self) -> Option<f64> {
+        Some(360.0)
+    }
 }
@@ -0,0 +1,612 @@
+use super::{Point, Polygon};
+
+/// An area returned by polygon_union: its outline and the holes inside it
+pub type Region = (Polygon<Point<f64>>, Vec<Polygon<Point<f64>>>);
+
+///
+/// Returns the regions covered by either of two polygons
+///
+/// Each region is returned as its outline and the holes inside it. Overlapping polygons produce
+/// one region around both, which has holes where the polygons enclose an area that neither of
+/// them covers. Polygons that do not overlap are returned as two regions, and a polygon inside
+/// the other one is absorbed into it.
+///
+/// Outlines are clockwise and holes are counterclockwise, whatever the winding of a and b.
+///
+/// The polygons must be simple (not self-intersecting). Polygons that only touch at a vertex or
+/// along part of an edge are not supported and may produce incorrect results.
+///
+pub fn polygon_union(a: &Polygon<Point<f64>>, b: &Polygon<Point<f64>>) -> Vec<Region> {
+    if a.points().len() < 3 || b.points().len() < 3 {
+        return [a, b].iter()
+            .filter(|polygon| polygon.points().len() >= 3)
+            .map(|&polygon| (clockwise(polygon.clone()), Vec::new()))
+            .collect();
+    }
+    match clip(a, b, Operation::Union) {
+        Some(mut rings) => {
+            // The outlines cross, so the union is one connected region. Its outline encloses
+            // all the other rings, which are holes.
+            let outline_index = (0..rings.len())
+                .max_by(|&left, &right| rings[left].signed_area().abs().partial_cmp(&rings[right].signed_area().abs()).unwrap())
+                .unwrap();
+            let outline = clockwise(rings.remove(outline_index));
+            let holes = rings.into_iter().map(counterclockwise).collect();
+            vec![(outline, holes)]
+        }
+        None => {
+            if b.contains(&a.points()[0]) {
+                vec![(clockwise(b.clone()), Vec::new())]
+            } else if a.contains(&b.points()[0]) {
+                vec![(clockwise(a.clone()), Vec::new())]
+            } else {
+                vec![(clockwise(a.clone()), Vec::new()), (clockwise(b.clone()), Vec::new())]
+            }
+        }
+    }
+}
+
+///
+/// Returns the regions covered by both of two polygons
+///
+/// The regions never have holes, because the polygons do not. Each region is clockwise,
+/// whatever the winding of a and b.
+///
+/// The polygons must be simple (not self-intersecting). Polygons that only touch at a vertex or
+/// along part of an edge are not supported and may produce incorrect results.
+///
+pub fn polygon_intersection(a: &Polygon<Point<f64>>, b: &Polygon<Point<f64>>) -> Vec<Polygon<Point<f64>>> {
+    if a.points().len() < 3 || b.points().len() < 3 {
+        return Vec::new();
+    }
+    match clip(a, b, Operation::Intersection) {
+        Some(polygons) => polygons.into_iter().map(clockwise).collect(),
+        None => {
+            if b.contains(&a.points()[0]) {
+                vec![clockwise(a.clone())]
+            } else if a.contains(&b.points()[0]) {
+                vec![clockwise(b.clone())]
+            } else {
+                Vec::new()
+            }
+        }
+    }
+}
+
+///
+/// Returns count points spaced evenly by distance along a polyline
+///
+/// The first and last points are the first and last points of the polyline. If count is 1,
+/// this returns only the first point. If the polyline has no length, every point is its
+/// first point.
+///
+pub fn resample_polyline(points: &[Point<f64>], count: usize) -> Vec<Point<f64>> {
+    if points.is_empty() || count == 0 {
+        return Vec::new();
+    }
+    let lengths: Vec<f64> = points.windows(2)
+        .map(|pair| f64::hypot(pair[1].x - pair[0].x, pair[1].y - pair[0].y))
+        .collect();
+    let total: f64 = lengths.iter().sum();
+    if count == 1 || total == 0.0 {
+        return vec![points[0].clone(); count];
+    }
+
+    let mut resampled = Vec::with_capacity(count);
+    resampled.push(points[0].clone());
+    let mut segment = 0;
+    // The distance along the polyline to the start of the current segment
+    let mut segment_start = 0.0;
+    for i in 1..(count - 1) {
+        let distance = total * i as f64 / (count - 1) as f64;
+        while segment < lengths.len() - 1 && segment_start + lengths[segment] < distance {
+            segment_start += lengths[segment];
+            segment += 1;
+        }
+        let t = if lengths[segment] > 0.0 { (distance - segment_start) / lengths[segment] } else { 0.0 };
+        let start = &points[segment];
+        let end = &points[segment + 1];
+        resampled.push(start.clone() + (end.clone() - start.clone()) * t.min(1.0));
+    }
+    resampled.push(points[points.len() - 1].clone());
+    resampled
+}
+
+///
+/// Returns the pixels on a line from a to b, including both ends, using Bresenham's algorithm
+///
+/// The pixels are in order from a to b, and each one is next to the one before it, either
+/// along an axis or diagonally.
+///
+pub fn rasterize_line(a: &Point<i32>, b: &Point<i32>) -> Vec<Point<i32>> {
+    let dx = (b.x - a.x).abs();
+    let dy = -(b.y - a.y).abs();
+    let step_x = if a.x < b.x { 1 } else { -1 };
+    let step_y = if a.y < b.y { 1 } else { -1 };
+    let mut pixels = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    let (mut x, mut y) = (a.x, a.y);
+    let mut error = dx + dy;
+    loop {
+        pixels.push(Point { x, y });
+        if x == b.x && y == b.y {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+    pixels
+}
+
+///
+/// Builds a polygon one vertex at a time, and checks whether the vertices form a valid polygon
+///
+pub struct PolygonBuilder<P> {
+    points: Vec<P>,
+}
+
+impl<P> PolygonBuilder<P> {
+    /// Creates a builder with no vertices
+    pub fn new() -> PolygonBuilder<P> {
+        PolygonBuilder {
+            points: Vec::new(),
+        }
+    }
+    /// Adds a vertex after the current last vertex
+    pub fn push(&mut self, point: P) {
+        self.points.push(point)
+    }
+    /// Removes and returns the last vertex, or returns None if there are no vertices
+    pub fn pop(&mut self) -> Option<P> {
+        self.points.pop()
+    }
+    /// Returns the vertices that have been added
+    pub fn points(&self) -> &[P] {
+        &self.points
+    }
+}
+
+impl PolygonBuilder<Point<f64>> {
+    ///
+    /// Returns true if the vertices form a valid polygon
+    ///
+    /// A valid polygon has at least three vertices, encloses some area, and is simple:
+    /// no edge touches any other edge except the edges next to it, at their shared vertices.
+    ///
+    pub fn is_valid(&self) -> bool {
+        let points = &self.points;
+        let count = points.len();
+        if count < 3 || Polygon::new(points).signed_area() == 0.0 {
+            return false;
+        }
+        for i in 0..count {
+            // Edges next to each other share a vertex, so only later edges that are not next to
+            // this edge need to be checked
+            for j in (i + 2)..count {
+                if i == 0 && j == count - 1 {
+                    continue;
+                }
+                if segments_touch(&points[i], &points[i + 1], &points[j], &points[(j + 1) % count]) {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+    /// Returns a polygon with the vertices that have been added, or None if they do not form
+    /// a valid polygon
+    pub fn build(self) -> Option<Polygon<Point<f64>>> {
+        if self.is_valid() {
+            Some(Polygon::new(&self.points))
+        } else {
+            None
+        }
+    }
+}
+
+impl<P> Default for PolygonBuilder<P> {
+    fn default() -> Self {
+        PolygonBuilder::new()
+    }
+}
+
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+enum Operation {
+    Union,
+    Intersection,
+}
+
+/// A vertex of a polygon, or a place where its outline crosses the other polygon's outline
+struct Node {
+    point: Point<f64>,
+    /// Index of the same crossing in the other polygon's list of nodes, if this is a crossing
+    neighbor: Option<usize>,
+    /// True if the outline enters the other polygon at this crossing
+    entry: bool,
+    visited: bool,
+}
+
+///
+/// Combines two polygons using the Greiner-Hormann algorithm
+///
+/// Returns None if the outlines of the polygons do not cross.
+///
+fn clip(a: &Polygon<Point<f64>>, b: &Polygon<Point<f64>>, operation: Operation) -> Option<Vec<Polygon<Point<f64>>>> {
+    let a_points = a.points();
+    let b_points = b.points();
+
+    // Each crossing is (edge of a, position along that edge, edge of b, position along that edge, point)
+    let mut crossings = Vec::new();
+    for i in 0..a_points.len() {
+        let a_start = &a_points[i];
+        let a_end = &a_points[(i + 1) % a_points.len()];
+        for j in 0..b_points.len() {
+            let b_start = &b_points[j];
+            let b_end = &b_points[(j + 1) % b_points.len()];
+            if let Some((alpha, beta)) = segment_intersection(a_start, a_end, b_start, b_end) {
+                let point = a_start.clone() + (a_end.clone() - a_start.clone()) * alpha;
+                crossings.push((i, alpha, j, beta, point));
+            }
+        }
+    }
+    if crossings.is_empty() {
+        return None;
+    }
+
+    let (mut a_nodes, a_indices) = build_nodes(a_points, &crossings, |crossing| (crossing.0, crossing.1));
+    let (mut b_nodes, b_indices) = build_nodes(b_points, &crossings, |crossing| (crossing.2, crossing.3));
+    for (&a_index, &b_index) in a_indices.iter().zip(b_indices.iter()) {
+        a_nodes[a_index].neighbor = Some(b_index);
+        b_nodes[b_index].neighbor = Some(a_index);
+    }
+    // A union follows the outside of each polygon instead of the inside, which is the same as
+    // traversing with every crossing's direction reversed
+    let invert = operation == Operation::Union;
+    mark_entries(&mut a_nodes, b.contains(&a_points[0]) != invert);
+    mark_entries(&mut b_nodes, a.contains(&b_points[0]) != invert);
+
+    let mut polygons = Vec::new();
+    let mut lists = [a_nodes, b_nodes];
+    while let Some(start) = lists[0].iter().position(|node| node.neighbor.is_some() && !node.visited) {
+        let mut points = Vec::new();
+        let mut list = 0;
+        let mut index = start;
+        while !lists[list][index].visited {
+            lists[list][index].visited = true;
+            let neighbor = lists[list][index].neighbor.unwrap();
+            lists[1 - list][neighbor].visited = true;
+
+            let forward = lists[list][index].entry;
+            points.push(lists[list][index].point.clone());
+            let length = lists[list].len();
+            loop {
+                index = if forward { (index + 1) % length } else { (index + length - 1) % length };
+                if lists[list][index].neighbor.is_some() {
+                    break;
+                }
+                points.push(lists[list][index].point.clone());
+            }
+            index = lists[list][index].neighbor.unwrap();
+            list = 1 - list;
+        }
+        polygons.push(Polygon::new(&points));
+    }
+    Some(polygons)
+}
+
+/// Returns a polygon with its points in clockwise order
+fn clockwise(mut polygon: Polygon<Point<f64>>) -> Polygon<Point<f64>> {
+    if !polygon.is_clockwise() {
+        polygon.reverse_winding();
+    }
+    polygon
+}
+
+/// Returns a polygon with its points in counterclockwise order
+fn counterclockwise(mut polygon: Polygon<Point<f64>>) -> Polygon<Point<f64>> {
+    if polygon.is_clockwise() {
+        polygon.reverse_winding();
+    }
+    polygon
+}
+
+///
+/// Creates the list of nodes for one polygon, with the crossings inserted in order along each edge
+///
+/// position returns the edge index and position along the edge of a crossing. Along with the
+/// nodes, this returns the index of each crossing in the list of nodes.
+///
+fn build_nodes<F>(points: &[Point<f64>], crossings: &[(usize, f64, usize, f64, Point<f64>)], position: F) -> (Vec<Node>, Vec<usize>)
+    where F: Fn(&(usize, f64, usize, f64, Point<f64>)) -> (usize, f64)
+{
+    let mut nodes = Vec::with_capacity(points.len() + crossings.len());
+    let mut indices = vec![0; crossings.len()];
+    for (edge, point) in points.iter().enumerate() {
+        nodes.push(Node { point: point.clone(), neighbor: None, entry: false, visited: false });
+        let mut on_edge: Vec<(usize, f64)> = crossings.iter()
+            .enumerate()
+            .map(|(i, crossing)| (i, position(crossing)))
+            .filter(|&(_, (crossing_edge, _))| crossing_edge == edge)
+            .map(|(i, (_, along))| (i, along))
+            .collect();
+        on_edge.sort_by(|left, right| left.1.partial_cmp(&right.1).unwrap());
+        for (i, _) in on_edge {
+            indices[i] = nodes.len();
+            // The neighbor is filled in after both lists are built
+            nodes.push(Node { point: crossings[i].4.clone(), neighbor: Some(0), entry: false, visited: false });
+        }
+    }
+    (nodes, indices)
+}
+
+/// Marks each crossing as an entry or exit, given whether the first node is inside the other polygon
+fn mark_entries(nodes: &mut [Node], mut inside: bool) {
+    for node in nodes.iter_mut().filter(|node| node.neighbor.is_some()) {
+        node.entry = !inside;
+        inside = !inside;
+    }
+}
+
+///
+/// Returns the positions along two line segments where they cross, as fractions of each segment's
+/// length, or None if they do not cross
+///
+/// Segments that are parallel or only touch at an endpoint are not considered to cross.
+///
+fn segment_intersection(a_start: &Point<f64>, a_end: &Point<f64>, b_start: &Point<f64>, b_end: &Point<f64>) -> Option<(f64, f64)> {
+    let a = a_end.clone() - a_start.clone();
+    let b = b_end.clone() - b_start.clone();
+    let denominator = a.x * b.y - a.y * b.x;
+    if denominator == 0.0 {
+        return None;
+    }
+    let offset = b_start.clone() - a_start.clone();
+    let alpha = (offset.x * b.y - offset.y * b.x) / denominator;
+    let beta = (offset.x * a.y - offset.y * a.x) / denominator;
+    if alpha > 0.0 && alpha < 1.0 && beta > 0.0 && beta < 1.0 {
+        Some((alpha, beta))
+    } else {
+        None
+    }
+}
+
+/// Returns true if two line segments cross or touch each other
+pub(crate) fn segments_touch(a_start: &Point<f64>, a_end: &Point<f64>, b_start: &Point<f64>, b_end: &Point<f64>) -> bool {
+    // Which side of each segment the ends of the other segment are on
+    let orientation = |start: &Point<f64>, end: &Point<f64>, point: &Point<f64>| {
+        (end.x - start.x) * (point.y - start.y) - (end.y - start.y) * (point.x - start.x)
+    };
+    // For a point known to be on the line through a segment, whether it is within the segment
+    let within = |start: &Point<f64>, end: &Point<f64>, point: &Point<f64>| {
+        point.x >= start.x.min(end.x) && point.x <= start.x.max(end.x)
+            && point.y >= start.y.min(end.y) && point.y <= start.y.max(end.y)
+    };
+    let a_b_start = orientation(a_start, a_end, b_start);
+    let a_b_end = orientation(a_start, a_end, b_end);
+    let b_a_start = orientation(b_start, b_end, a_start);
+    let b_a_end = orientation(b_start, b_end, a_end);
+    if a_b_start * a_b_end < 0.0 && b_a_start * b_a_end < 0.0 {
+        return true;
+    }
+    (a_b_start == 0.0 && within(a_start, a_end, b_start))
+        || (a_b_end == 0.0 && within(a_start, a_end, b_end))
+        || (b_a_start == 0.0 && within(b_start, b_end, a_start))
+        || (b_a_end == 0.0 && within(b_start, b_end, a_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Polygon<Point<f64>> {
+        Polygon::new(&[
+            Point { x, y },
+            Point { x: x + size, y },
+            Point { x: x + size, y: y + size },
+            Point { x, y: y + size },
+        ])
+    }
+
+    fn assert_same_points(expected: &[Point<f64>], polygon: &Polygon<Point<f64>>) {
+        assert_eq!(expected.len(), polygon.points().len());
+        for point in expected {
+            assert!(polygon.points().iter().any(|other| (other.x - point.x).abs() < 1e-9 && (other.y - point.y).abs() < 1e-9),
+                "{:?} is not in {:?}", point, polygon);
+        }
+    }
+
+    fn reversed(mut polygon: Polygon<Point<f64>>) -> Polygon<Point<f64>> {
+        polygon.reverse_winding();
+        polygon
+    }
+
+    #[test]
+    fn test_intersection_overlapping_squares() {
+        let result = polygon_intersection(&square(0.0, 0.0, 2.0), &square(1.0, 1.0, 2.0));
+        assert_eq!(1, result.len());
+        assert_same_points(square(1.0, 1.0, 1.0).points(), &result[0]);
+        assert!(result[0].is_clockwise());
+    }
+    #[test]
+    fn test_union_overlapping_squares() {
+        let result = polygon_union(&square(0.0, 0.0, 2.0), &square(1.0, 1.0, 2.0));
+        assert_eq!(1, result.len());
+        let (outline, holes) = &result[0];
+        assert!(outline.is_clockwise());
+        assert!(holes.is_empty());
+        let expected = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 2.0, y: 0.0 },
+            Point { x: 2.0, y: 1.0 },
+            Point { x: 3.0, y: 1.0 },
+            Point { x: 3.0, y: 3.0 },
+            Point { x: 1.0, y: 3.0 },
+            Point { x: 1.0, y: 2.0 },
+            Point { x: 0.0, y: 2.0 },
+        ];
+        assert_same_points(&expected, outline);
+    }
+    #[test]
+    fn test_union_with_hole() {
+        // A bar across the top of a U shape closes it, leaving a hole inside
+        let u = Polygon::new(&[
+            Point { x: 0.0, y: 0.0 },
+            Point { x: 0.0, y: 3.0 },
+            Point { x: 1.0, y: 3.0 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 2.0, y: 1.0 },
+            Point { x: 2.0, y: 3.0 },
+            Point { x: 3.0, y: 3.0 },
+            Point { x: 3.0, y: 0.0 },
+        ]);
+        let bar = Polygon::new(&[
+            Point { x: -0.5, y: 2.5 },
+            Point { x: -0.5, y: 3.5 },
+            Point { x: 3.5, y: 3.5 },
+            Point { x: 3.5, y: 2.5 },
+        ]);
+        let result = polygon_union(&u, &bar);
+        assert_eq!(1, result.len());
+        let (outline, holes) = &result[0];
+        assert!(outline.is_clockwise());
+        assert!((outline.signed_area() + 11.5).abs() < 1e-9);
+        assert_eq!(1, holes.len());
+        assert!(!holes[0].is_clockwise());
+        assert!((holes[0].signed_area() - 1.5).abs() < 1e-9);
+        assert_same_points(&[
+            Point { x: 1.0, y: 2.5 },
+            Point { x: 1.0, y: 1.0 },
+            Point { x: 2.0, y: 1.0 },
+            Point { x: 2.0, y: 2.5 },
+        ], &holes[0]);
+    }
+    #[test]
+    fn test_separate_squares() {
+        let a = square(0.0, 0.0, 1.0);
+        let b = square(5.0, 5.0, 1.0);
+        assert!(polygon_intersection(&a, &b).is_empty());
+        assert_eq!(vec![(reversed(a.clone()), Vec::new()), (reversed(b.clone()), Vec::new())], polygon_union(&a, &b));
+    }
+    #[test]
+    fn test_nested_squares() {
+        let outer = square(0.0, 0.0, 4.0);
+        let inner = square(1.0, 1.0, 1.0);
+        assert_eq!(vec![reversed(inner.clone())], polygon_intersection(&outer, &inner));
+        assert_eq!(vec![(reversed(outer.clone()), Vec::new())], polygon_union(&inner, &outer));
+        // Clockwise input stays clockwise
+        assert_eq!(vec![(reversed(outer.clone()), Vec::new())], polygon_union(&reversed(inner), &reversed(outer.clone())));
+    }
+
+    #[test]
+    fn test_resample_straight_line() {
+        let line = [Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 2.0 }, Point { x: 4.0, y: 8.0 }];
+        let resampled = resample_polyline(&line, 5);
+        assert_eq!(5, resampled.len());
+        for (i, point) in resampled.iter().enumerate() {
+            assert!((point.x - i as f64).abs() < 1e-9);
+            assert!((point.y - 2.0 * i as f64).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn test_resample_keeps_endpoints() {
+        let path = [Point { x: 0.0, y: 0.0 }, Point { x: 3.0, y: 0.0 }, Point { x: 3.0, y: 1.0 }, Point { x: -2.0, y: 1.0 }];
+        for &count in [2, 3, 7, 20].iter() {
+            let resampled = resample_polyline(&path, count);
+            assert_eq!(count, resampled.len());
+            assert_eq!(path[0], resampled[0]);
+            assert_eq!(path[3], resampled[count - 1]);
+        }
+        // The total length is 9, so 4 points are 3 apart along the path
+        let resampled = resample_polyline(&path, 4);
+        assert_eq!(Point { x: 3.0, y: 0.0 }, resampled[1]);
+        assert_eq!(Point { x: 1.0, y: 1.0 }, resampled[2]);
+    }
+    #[test]
+    fn test_rasterize_horizontal() {
+        let pixels = rasterize_line(&Point { x: 2, y: 5 }, &Point { x: 6, y: 5 });
+        let expected: Vec<Point<i32>> = (2..7).map(|x| Point { x, y: 5 }).collect();
+        assert_eq!(expected, pixels);
+        // Backward
+        let mut reversed = expected.clone();
+        reversed.reverse();
+        assert_eq!(reversed, rasterize_line(&Point { x: 6, y: 5 }, &Point { x: 2, y: 5 }));
+    }
+    #[test]
+    fn test_rasterize_diagonal() {
+        let pixels = rasterize_line(&Point { x: 0, y: 0 }, &Point { x: 4, y: -4 });
+        let expected: Vec<Point<i32>> = (0..5).map(|i| Point { x: i, y: -i }).collect();
+        assert_eq!(expected, pixels);
+    }
+    #[test]
+    fn test_rasterize_steep() {
+        let pixels = rasterize_line(&Point { x: 0, y: 0 }, &Point { x: 2, y: 5 });
+        assert_eq!(vec![
+            Point { x: 0, y: 0 },
+            Point { x: 0, y: 1 },
+            Point { x: 1, y: 2 },
+            Point { x: 1, y: 3 },
+            Point { x: 2, y: 4 },
+            Point { x: 2, y: 5 },
+        ], pixels);
+    }
+    #[test]
+    fn test_rasterize_single_pixel() {
+        assert_eq!(vec![Point { x: 3, y: 3 }], rasterize_line(&Point { x: 3, y: 3 }, &Point { x: 3, y: 3 }));
+    }
+    #[test]
+    fn test_resample_degenerate() {
+        assert!(resample_polyline(&[], 3).is_empty());
+        let point = Point { x: 1.0, y: 1.0 };
+        assert_eq!(vec![point.clone(), point.clone()], resample_polyline(std::slice::from_ref(&point), 2));
+        assert_eq!(vec![point.clone()], resample_polyline(&[point, Point { x: 5.0, y: 1.0 }], 1));
+    }
+    #[test]
+    fn test_builder_triangle() {
+        let mut builder = PolygonBuilder::new();
+        builder.push(Point { x: 0.0, y: 0.0 });
+        builder.push(Point { x: 4.0, y: 0.0 });
+        assert!(!builder.is_valid());
+        builder.push(Point { x: 2.0, y: 3.0 });
+        assert!(builder.is_valid());
+        let triangle = builder.build().unwrap();
+        assert_eq!(3, triangle.points().len());
+    }
+    #[test]
+    fn test_builder_bowtie() {
+        let mut builder = PolygonBuilder::new();
+        builder.push(Point { x: 0.0, y: 0.0 });
+        builder.push(Point { x: 2.0, y: 2.0 });
+        builder.push(Point { x: 2.0, y: 0.0 });
+        builder.push(Point { x: 0.0, y: 2.0 });
+        assert!(!builder.is_valid());
+        assert_eq!(None, builder.build());
+    }
+    #[test]
+    fn test_builder_pop_to_valid() {
+        let mut builder = PolygonBuilder::new();
+        builder.push(Point { x: 0.0, y: 0.0 });
+        builder.push(Point { x: 2.0, y: 0.0 });
+        builder.push(Point { x: 2.0, y: 2.0 });
+        builder.push(Point { x: 0.0, y: 2.0 });
+        assert!(builder.is_valid());
+        // This vertex makes the last edge cross the first edge
+        builder.push(Point { x: 1.0, y: -1.0 });
+        assert!(!builder.is_valid());
+        assert_eq!(Some(Point { x: 1.0, y: -1.0 }), builder.pop());
+        assert!(builder.is_valid());
+    }
+    #[test]
+    fn test_builder_collinear() {
+        let mut builder = PolygonBuilder::new();
+        builder.push(Point { x: 0.0, y: 0.0 });
+        builder.push(Point { x: 1.0, y: 1.0 });
+        builder.push(Point { x: 2.0, y: 2.0 });
+        assert!(!builder.is_valid());
+    }
+}
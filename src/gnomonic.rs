@@ -0,0 +1,123 @@
+use super::{LatLon, Point};
+use projection::{Projection, ProjectionAspect, azimuthal_aspect, azimuthal_unproject};
+
+///
+/// A gnomonic projection, which shows every great circle as a straight line
+///
+/// The earth is a sphere with a radius of 1, and the map is tangent to it at the center, which
+/// projects to the origin. Distortion grows quickly away from the center, and positions 90
+/// degrees or more from the center cannot be shown at all: project() returns a point with NaN
+/// coordinates for them, and try_project() returns ProjectionError::OutOfBounds.
+///
+pub struct GnomonicProjection {
+    /// The position where the map touches the sphere
+    center: LatLon,
+}
+
+impl GnomonicProjection {
+    pub fn new(center: LatLon) -> GnomonicProjection {
+        GnomonicProjection {
+            center,
+        }
+    }
+
+    /// Returns the position where the map touches the sphere
+    pub fn center(&self) -> LatLon {
+        self.center.clone()
+    }
+    /// Sets the position where the map touches the sphere
+    pub fn set_center(&mut self, center: LatLon) {
+        self.center = center;
+    }
+}
+
+impl Projection for GnomonicProjection {
+    fn project(&self, position: &LatLon) -> Point<f64> {
+        let center_lat = self.center.latitude.to_radians();
+        let lat = position.latitude.to_radians();
+        let delta_lon = (position.longitude - self.center.longitude).to_radians();
+        // Cosine of the angular distance from the center
+        let cos_c = center_lat.sin() * lat.sin() + center_lat.cos() * lat.cos() * delta_lon.cos();
+        // Rounding leaves positions on the horizon slightly in front of it
+        if cos_c <= f64::EPSILON {
+            return Point { x: f64::NAN, y: f64::NAN };
+        }
+        let x = lat.cos() * delta_lon.sin() / cos_c;
+        let y = (center_lat.cos() * lat.sin() - center_lat.sin() * lat.cos() * delta_lon.cos()) / cos_c;
+
+        Point { x, y }
+    }
+    fn unproject(&self, position: &Point<f64>) -> LatLon {
+        azimuthal_unproject(&self.center, position, f64::atan)
+    }
+
+    fn natural_origin(&self) -> Option<LatLon> {
+        Some(self.center())
+    }
+    fn set_natural_origin(&mut self, origin: LatLon) -> bool {
+        self.set_center(origin);
+        true
+    }
+    fn aspect(&self) -> ProjectionAspect {
+        azimuthal_aspect(&self.center)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use projection::ProjectionError;
+    use ::{Latitude, Longitude};
+    use sphere::Vector3;
+
+    fn seattle() -> LatLon {
+        LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) }
+    }
+
+    #[test]
+    fn test_gnomonic_identity() {
+        let gnomonic = GnomonicProjection::new(seattle());
+        assert!(gnomonic.project(&seattle()).approx_eq(&Point::origin(), 1e-9));
+        let ll = LatLon { latitude: Latitude(37.4096), longitude: Longitude(-122.299) };
+        let projected = gnomonic.project(&ll);
+        let unprojected = gnomonic.unproject(&projected);
+
+        println!("Gnomonic: {:?} => {:?} => {:?}", ll, projected, unprojected);
+        assert!(ll.approx_eq(&unprojected, 1e-9));
+    }
+    #[test]
+    fn test_gnomonic_great_circle_straight() {
+        let gnomonic = GnomonicProjection::new(seattle());
+        // Three points along the great circle from Seattle to Tokyo
+        let start = Vector3::from_latlon(&LatLon { latitude: Latitude(47.45), longitude: Longitude(-122.31) });
+        let end = Vector3::from_latlon(&LatLon { latitude: Latitude(35.55), longitude: Longitude(139.78) });
+        let points: Vec<Point<f64>> = [0.0, 0.3, 0.6].iter()
+            .map(|&t| gnomonic.try_project(&start.slerp(end, t).to_latlon()).unwrap())
+            .collect();
+        let first = points[1].clone() - points[0].clone();
+        let second = points[2].clone() - points[0].clone();
+        assert!(first.magnitude() > 0.1);
+        assert!(first.normalized().cross(&second.normalized()).abs() < 1e-9);
+    }
+    #[test]
+    fn test_gnomonic_hemisphere_limit() {
+        let center = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        let gnomonic = GnomonicProjection::new(center.clone());
+        let near_edge = gnomonic.try_project(&LatLon { latitude: Latitude(0.0), longitude: Longitude(89.0) }).unwrap();
+        assert!(near_edge.x > 50.0);
+        // Points on the horizon and beyond it cannot be shown
+        let horizon = LatLon { latitude: Latitude(0.0), longitude: Longitude(90.0) };
+        assert_eq!(Err(ProjectionError::OutOfBounds), gnomonic.try_project(&horizon));
+        let behind = LatLon { latitude: Latitude(10.0), longitude: Longitude(120.0) };
+        assert_eq!(Err(ProjectionError::OutOfBounds), gnomonic.try_project(&behind));
+        assert_eq!(Err(ProjectionError::OutOfBounds), gnomonic.try_project(&center.antipode()));
+    }
+    #[test]
+    fn test_gnomonic_aspect() {
+        let mut gnomonic = GnomonicProjection::new(LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) });
+        assert_eq!(ProjectionAspect::Normal, gnomonic.aspect());
+        assert!(gnomonic.set_natural_origin(seattle()));
+        assert_eq!(ProjectionAspect::Oblique, gnomonic.aspect());
+        assert_eq!(Some(seattle()), gnomonic.natural_origin());
+    }
+}
@@ -1,17 +1,22 @@
-use super::LatLonRect;
+use super::{LatLon, LatLonRect, Point};
 use projection::Projection;
+use canvas::Canvas;
+use navigation;
+
+/// The distance, in radians of arc, between the points used to estimate geographic_direction
+const DIRECTION_STEP: f64 = 1e-6;
 
 ///
 /// Trait for required functionality for map layers
 ///
 pub trait Layer {
     ///
-    /// Draws this layer
+    /// Draws this layer onto a canvas
     ///
     /// The provided Projection is a projection that can map between latitude/longitude and
-    /// display coordinates.
+    /// display coordinates, which are the coordinates that the canvas uses.
     ///
-    fn draw(&self, projection: &Projection, x: i32, y: i32, width: i32, height: i32);
+    fn draw(&self, canvas: &mut dyn Canvas, projection: &dyn Projection, x: i32, y: i32, width: i32, height: i32);
 
     ///
     /// Returns a LatLonRect that bounds the items that this layer displays, or None if
@@ -19,3 +24,71 @@ pub trait Layer {
     ///
     fn bounds(&self) -> Option<LatLonRect>;
 }
+
+
+///
+/// Returns true if a feature with the provided bounds appears at least min_pixels wide or tall
+/// when projected
+///
+/// Layers can call this from draw, with the projection that they are given, to skip features
+/// that would be too small to see. The size is measured between the projected corners of the
+/// bounds.
+///
+pub fn should_draw_feature(projection: &dyn Projection, bounds: &LatLonRect, min_pixels: f64) -> bool {
+    let (min, max) = projected_extent(projection, &bounds.corners());
+    let size = max - min;
+    size.x.max(size.y) >= min_pixels
+}
+
+///
+/// Returns a vector of length 1 that points in a compass direction at a position, after
+/// projection
+///
+/// bearing_degrees is measured clockwise from true north. The direction is estimated from the
+/// projections of the position and a point a very short distance away in the compass direction.
+/// If those are the same or are not finite, both coordinates of the result are NaN.
+///
+pub fn geographic_direction(projection: &dyn Projection, at: &LatLon, bearing_degrees: f64) -> Point<f64> {
+    let ahead = navigation::rhumb_destination(at, bearing_degrees, DIRECTION_STEP, 1.0);
+    let difference = projection.project(&ahead) - projection.project(at);
+    difference.clone() * (1.0 / f64::hypot(difference.x, difference.y))
+}
+
+///
+/// Returns the size to draw something at a zoom level, for things like markers that grow as
+/// the map zooms in
+///
+/// At reference_zoom the size is base_size, and each zoom level above or below that multiplies
+/// or divides it by scale_per_level. Fractional zoom levels change the size smoothly.
+///
+pub fn scaled_size(base_size: f64, zoom: f64, reference_zoom: f64, scale_per_level: f64) -> f64 {
+    base_size * scale_per_level.powf(zoom - reference_zoom)
+}
+
+///
+/// Returns the minimum and maximum coordinates of a non-empty set of points after projecting them
+///
+pub(crate) fn projected_extent(projection: &dyn Projection, points: &[LatLon]) -> (Point<f64>, Point<f64>) {
+    let first = projection.project(&points[0]);
+    points[1..].iter()
+        .map(|point| projection.project(point))
+        .fold((first.clone(), first), |(min, max), point| (min.min(&point), max.max(&point)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::close_enough;
+
+    #[test]
+    fn test_scaled_size_reference() {
+        assert_eq!(12.0, scaled_size(12.0, 5.0, 5.0, 1.5));
+    }
+    #[test]
+    fn test_scaled_size_levels() {
+        assert!(close_enough(18.0, scaled_size(12.0, 6.0, 5.0, 1.5)));
+        assert!(close_enough(8.0, scaled_size(12.0, 4.0, 5.0, 1.5)));
+        // Half a level is half of the scale in log space
+        assert!(close_enough(12.0 * f64::sqrt(1.5), scaled_size(12.0, 5.5, 5.0, 1.5)));
+    }
+}
@@ -1,6 +1,14 @@
-use std::ops::{Add, Sub, Mul};
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+use std::ops::{Add, Sub, Mul, Neg};
 use std::iter::FromIterator;
 
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
 /// Provides projections between a sphere and the map view
 pub mod projection;
 /// Implements a stereographic projection
@@ -9,16 +17,51 @@ pub mod stereographic;
 pub mod equirectangular;
 /// Implements a Miller cylindrical projection
 pub mod miller;
+/// Implements a spherical Mercator projection
+pub mod mercator;
+/// Implements an orthographic projection, which shows the earth as a globe
+pub mod orthographic;
+/// Implements a gnomonic projection, which shows great circles as straight lines
+pub mod gnomonic;
+/// Creates projections by name
+pub mod registry;
 /// Layers that can be drawn on the map
 pub mod layer;
+/// The interface that layers draw through
+pub mod canvas;
+/// A layer that shows the direction of north
+pub mod compass;
+/// A layer that shows markers at points
+pub mod marker;
+/// A layer that shows paths
+pub mod polyline;
+/// A layer that shows filled polygons
+pub mod polygon;
 /// Represents a map
 pub mod map;
+/// Building, combining, resampling, and rasterizing polygons and polylines
+pub mod geometry;
+/// Navigation calculations such as dead reckoning, turn directions, and path bearings
+pub mod navigation;
+/// Parsing and formatting positions as text
+pub mod text;
+/// Projections and other utilities for testing code that uses this crate
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
+/// Vector math on the unit sphere
+mod sphere;
+
+use sphere::Vector3;
 
 /// Represents a latitude, in degrees
 #[derive(Debug,Copy,Clone,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Latitude(pub f64);
 /// Represents a longitude, in degrees
 #[derive(Debug,Copy,Clone,PartialEq,PartialOrd)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Longitude(pub f64);
 
 impl Latitude {
@@ -33,14 +76,14 @@ impl Longitude {
         f64::to_radians(self.into())
     }
 }
-impl Into<f64> for Latitude {
-    fn into(self) -> f64 {
-        self.0
+impl From<Latitude> for f64 {
+    fn from(value: Latitude) -> Self {
+        value.0
     }
 }
-impl Into<f64> for Longitude {
-    fn into(self) -> f64 {
-        self.0
+impl From<Longitude> for f64 {
+    fn from(value: Longitude) -> Self {
+        value.0
     }
 }
 impl From<f64> for Latitude {
@@ -80,6 +123,7 @@ impl Add for Longitude {
 
 /// Stores a latitude and longitude
 #[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LatLon {
     /// Latitude, degrees
     pub latitude: Latitude,
@@ -88,6 +132,16 @@ pub struct LatLon {
 }
 
 impl LatLon {
+    ///
+    /// Returns true if the latitude and longitude of this position are each within epsilon_deg
+    /// degrees of those of another position
+    ///
+    /// Longitudes are compared without wrapping, so 180 and -180 degrees are not equal.
+    ///
+    pub fn approx_eq(&self, other: &LatLon, epsilon_deg: f64) -> bool {
+        (self.latitude.0 - other.latitude.0).abs() <= epsilon_deg
+            && (self.longitude.0 - other.longitude.0).abs() <= epsilon_deg
+    }
     /// Returns a LatLon that is dimetrically opposite from this (on the other side of the planet)
     pub fn antipode(&self) -> LatLon {
         LatLon {
@@ -95,12 +149,137 @@ impl LatLon {
             longitude: normalize_longitude(self.longitude + Longitude(180.0)),
         }
     }
+
+    ///
+    /// Returns the great-circle distance from this point to another point on a sphere with the
+    /// provided radius
+    ///
+    /// The result is in the same units as the radius.
+    ///
+    pub fn distance_to(&self, other: &LatLon, radius: f64) -> f64 {
+        // Haversine formula
+        let delta_latitude = (other.latitude - self.latitude).to_radians();
+        let delta_longitude = (other.longitude - self.longitude).to_radians();
+        let haversine = (delta_latitude / 2.0).sin().powi(2)
+            + self.latitude.to_radians().cos() * other.latitude.to_radians().cos() * (delta_longitude / 2.0).sin().powi(2);
+        // Rounding can make the haversine slightly larger than 1 for nearly antipodal points
+        let haversine = haversine.clamp(0.0, 1.0);
+        2.0 * f64::atan2(haversine.sqrt(), (1.0 - haversine).sqrt()) * radius
+    }
+    ///
+    /// Returns the initial bearing of the great-circle path from this point to another point,
+    /// in degrees clockwise from true north in the range [0, 360)
+    ///
+    pub fn bearing_to(&self, other: &LatLon) -> f64 {
+        let from_latitude = self.latitude.to_radians();
+        let to_latitude = other.latitude.to_radians();
+        let delta_longitude = (other.longitude - self.longitude).to_radians();
+        let y = delta_longitude.sin() * to_latitude.cos();
+        let x = from_latitude.cos() * to_latitude.sin() - from_latitude.sin() * to_latitude.cos() * delta_longitude.cos();
+        // Adding 360 to a bearing just below 0 can round to exactly 360
+        let bearing = (f64::atan2(y, x).to_degrees() + 360.0) % 360.0;
+        if bearing >= 360.0 { 0.0 } else { bearing }
+    }
+    ///
+    /// Returns the point reached by traveling a distance from this point along a great circle,
+    /// starting at a bearing in degrees clockwise from true north
+    ///
+    /// distance and radius must use the same units. The result is normalized, so paths that
+    /// cross a pole or the antimeridian give a latitude in [-90, 90] and a longitude in
+    /// [-180, 180].
+    ///
+    pub fn destination(&self, bearing_deg: f64, distance: f64, radius: f64) -> LatLon {
+        let angular_distance = distance / radius;
+        let bearing = bearing_deg.to_radians();
+        let (sin_start, cos_start) = self.latitude.to_radians().sin_cos();
+        let (sin_distance, cos_distance) = angular_distance.sin_cos();
+        let sin_end = (sin_start * cos_distance + cos_start * sin_distance * bearing.cos()).clamp(-1.0, 1.0);
+        let end_latitude = sin_end.asin();
+        let delta_longitude = f64::atan2(bearing.sin() * sin_distance * cos_start, cos_distance - sin_start * sin_end);
+        LatLon {
+            latitude: normalize_latitude(Latitude(end_latitude.to_degrees())),
+            longitude: normalize_longitude(self.longitude + Longitude(delta_longitude.to_degrees())),
+        }
+    }
+    ///
+    /// Returns a point along the great circle from this point to another point
+    ///
+    /// A fraction of 0 returns this point, and 1 returns the other point. Fractions between
+    /// them move along the great circle at a constant speed.
+    ///
+    /// Every great circle through two antipodal points connects them, so for nearly antipodal
+    /// points this uses the great circle through this point and the north pole (or, from a pole,
+    /// the prime meridian).
+    ///
+    pub fn interpolate(&self, other: &LatLon, fraction: f64) -> LatLon {
+        let start = Vector3::from_latlon(self);
+        let end = Vector3::from_latlon(other);
+        let angle = start.angle_to(end);
+        if angle.sin().abs() >= 1e-9 {
+            return start.slerp(end, fraction).to_latlon();
+        }
+        if angle < 1.0 {
+            return self.clone();
+        }
+        let north = Vector3 { x: 0.0, y: 0.0, z: 1.0 };
+        let axis = start.cross(north);
+        let axis = if axis.magnitude() < 1e-9 { Vector3 { x: 0.0, y: -1.0, z: 0.0 } } else { axis * (1.0 / axis.magnitude()) };
+        start.rotate(axis, angle * fraction).to_latlon()
+    }
+    ///
+    /// Returns the great-circle distance, in meters, from this point to another point on a
+    /// sphere with the earth's mean radius
+    ///
+    pub fn distance_to_earth(&self, other: &LatLon) -> f64 {
+        self.distance_to(other, EARTH_MEAN_RADIUS)
+    }
+
+    ///
+    /// Returns the latitude and longitude differences from this point to another point
+    ///
+    /// The longitude difference is in the range [-180, 180] and takes the shorter direction
+    /// around the earth, so an offset that crosses the antimeridian stays small.
+    ///
+    pub fn offset_to(&self, other: &LatLon) -> (Latitude, Longitude) {
+        (other.latitude - self.latitude, normalize_longitude(other.longitude - self.longitude))
+    }
+
+    ///
+    /// Rotates this point around a pivot point
+    ///
+    /// The rotation is about the axis that passes through the center of the earth and the
+    /// pivot, so this point moves along a circle of constant distance from the pivot.
+    /// Positive angles rotate clockwise when looking down at the pivot from above the surface,
+    /// in the same direction that compass bearings increase.
+    ///
+    pub fn rotate_around(&self, pivot: &LatLon, angle_degrees: f64) -> LatLon {
+        let axis = Vector3::from_latlon(pivot);
+        Vector3::from_latlon(self).rotate(axis, -angle_degrees.to_radians()).to_latlon()
+    }
+}
+
+///
+/// An edge of a LatLonRect
+///
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub enum Edge {
+    /// The edge along the north latitude
+    North,
+    /// The edge along the south latitude
+    South,
+    /// The edge along the east longitude
+    East,
+    /// The edge along the west longitude
+    West,
 }
 
 ///
 /// A rectangle in latitude and longitude
 ///
+/// With the serde feature, a rectangle is serialized as its north, south, east, and west bounds.
+///
 #[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LatLonRect {
     /// The north latitude (always >= south)
     north: Latitude,
@@ -115,10 +294,10 @@ pub struct LatLonRect {
 impl LatLonRect {
     pub fn from_bounds(north: Latitude, south: Latitude, east: Longitude, west: Longitude) -> LatLonRect {
         LatLonRect {
-            north: north,
-            south: south,
-            east: east,
-            west: west,
+            north,
+            south,
+            east,
+            west,
         }
     }
     pub fn from_corners(northwest: &LatLon, southeast: &LatLon) -> LatLonRect {
@@ -129,6 +308,27 @@ impl LatLonRect {
             west: northwest.longitude,
         }
     }
+    ///
+    /// Returns the smallest rectangle that contains all the provided points, or None if there
+    /// are no points
+    ///
+    /// The rectangle never crosses the antimeridian. A single point produces a rectangle
+    /// with no area at that point.
+    ///
+    pub fn from_points<I: IntoIterator<Item = LatLon>>(points: I) -> Option<LatLonRect> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut rect = LatLonRect {
+            north: first.latitude,
+            south: first.latitude,
+            east: first.longitude,
+            west: first.longitude,
+        };
+        for point in points {
+            rect.expand_to_include(&point);
+        }
+        Some(rect)
+    }
     pub fn north(&self) -> Latitude {
         self.north
     }
@@ -153,13 +353,190 @@ impl LatLonRect {
     pub fn set_west(&mut self, west: Longitude) {
         self.west = west
     }
+    /// Returns true if this rectangle has no area because its north and south edges are the same,
+    /// its east and west edges are the same, or both
+    pub fn is_empty(&self) -> bool {
+        self.north == self.south || self.east == self.west
+    }
+    /// Returns true if this rectangle is a single point
+    pub fn is_point(&self) -> bool {
+        self.north == self.south && self.east == self.west
+    }
+    /// Returns true if a point is inside this rectangle or on one of its edges
+    pub fn contains_point(&self, point: &LatLon) -> bool {
+        point.latitude.0 >= self.south.0 && point.latitude.0 <= self.north.0
+            && point.longitude.0 >= self.west.0 && point.longitude.0 <= self.east.0
+    }
+    /// Returns true if another rectangle is inside this rectangle, with its edges inside or on the
+    /// edges of this rectangle
+    pub fn contains_rect(&self, other: &LatLonRect) -> bool {
+        other.south.0 >= self.south.0 && other.north.0 <= self.north.0
+            && other.west.0 >= self.west.0 && other.east.0 <= self.east.0
+    }
+    ///
+    /// Returns the area that this rectangle and another rectangle both cover, or None if they
+    /// do not overlap
+    ///
+    /// Rectangles that only touch along an edge or at a corner have an intersection with
+    /// no area, which is_empty reports, instead of None.
+    ///
+    pub fn intersection(&self, other: &LatLonRect) -> Option<LatLonRect> {
+        let rect = LatLonRect {
+            north: Latitude(self.north.0.min(other.north.0)),
+            south: Latitude(self.south.0.max(other.south.0)),
+            east: Longitude(self.east.0.min(other.east.0)),
+            west: Longitude(self.west.0.max(other.west.0)),
+        };
+        if rect.north.0 >= rect.south.0 && rect.east.0 >= rect.west.0 {
+            Some(rect)
+        } else {
+            None
+        }
+    }
+    /// Returns the smallest rectangle that contains this rectangle and another rectangle
+    pub fn union(&self, other: &LatLonRect) -> LatLonRect {
+        LatLonRect {
+            north: Latitude(self.north.0.max(other.north.0)),
+            south: Latitude(self.south.0.min(other.south.0)),
+            east: Longitude(self.east.0.max(other.east.0)),
+            west: Longitude(self.west.0.min(other.west.0)),
+        }
+    }
+    /// Returns the point halfway between the north and south edges and halfway between the east
+    /// and west edges of this rectangle
+    pub fn center(&self) -> LatLon {
+        LatLon {
+            latitude: Latitude((self.north.0 + self.south.0) / 2.0),
+            // TODO antimeridian: this is on the wrong side of the earth for rectangles that cross it
+            longitude: Longitude((self.east.0 + self.west.0) / 2.0),
+        }
+    }
+    /// Moves the edges of this rectangle out as little as possible so that it contains a point
+    pub fn expand_to_include(&mut self, point: &LatLon) {
+        self.north = Latitude(self.north.0.max(point.latitude.0));
+        self.south = Latitude(self.south.0.min(point.latitude.0));
+        self.east = Longitude(self.east.0.max(point.longitude.0));
+        self.west = Longitude(self.west.0.min(point.longitude.0));
+    }
+    ///
+    /// Returns the smallest rectangle with edges on multiples of lat_step and lon_step degrees
+    /// that contains this rectangle
+    ///
+    /// For a rectangle that crosses the antimeridian, with its west edge east of its east edge,
+    /// the result also crosses the antimeridian. Edges are limited to the valid ranges of
+    /// latitude and longitude. A step that is not positive leaves those edges unchanged.
+    ///
+    pub fn snap_out(&self, lat_step: f64, lon_step: f64) -> LatLonRect {
+        let snap = |value: f64, step: f64, round: fn(f64) -> f64| {
+            if step > 0.0 { round(value / step) * step } else { value }
+        };
+        LatLonRect {
+            north: Latitude(snap(self.north.0, lat_step, f64::ceil).min(90.0)),
+            south: Latitude(snap(self.south.0, lat_step, f64::floor).max(-90.0)),
+            east: Longitude(snap(self.east.0, lon_step, f64::ceil).min(180.0)),
+            west: Longitude(snap(self.west.0, lon_step, f64::floor).max(-180.0)),
+        }
+    }
+    ///
+    /// Returns the edge of this rectangle that another rectangle is next to, or None if they
+    /// do not share an edge
+    ///
+    /// The rectangles share an edge if that edge of this rectangle is within tolerance degrees
+    /// of the opposite edge of the other rectangle and the edges overlap along their length.
+    /// Rectangles that overlap in area, or that only meet at a corner, do not share an edge.
+    /// East and west edges on opposite sides of the antimeridian, like 180 and -180, are the same.
+    ///
+    pub fn is_adjacent(&self, other: &LatLonRect, tolerance: f64) -> Option<Edge> {
+        let latitudes_overlap = self.north.0.min(other.north.0) - self.south.0.max(other.south.0) > 0.0;
+        if latitudes_overlap {
+            if same_longitude(self.east, other.west, tolerance) {
+                return Some(Edge::East);
+            }
+            if same_longitude(self.west, other.east, tolerance) {
+                return Some(Edge::West);
+            }
+        }
+        if self.longitudes_overlap(other) {
+            if (self.north.0 - other.south.0).abs() <= tolerance {
+                return Some(Edge::North);
+            }
+            if (self.south.0 - other.north.0).abs() <= tolerance {
+                return Some(Edge::South);
+            }
+        }
+        None
+    }
+    /// Returns true if the longitude ranges of this rectangle and another rectangle overlap
+    /// by more than a single longitude
+    fn longitudes_overlap(&self, other: &LatLonRect) -> bool {
+        let width = |rect: &LatLonRect| (rect.east.0 - rect.west.0).rem_euclid(360.0);
+        let (self_width, other_width) = (width(self), width(other));
+        [-360.0, 0.0, 360.0].iter().any(|shift| {
+            let west = self.west.0.max(other.west.0 + shift);
+            let east = (self.west.0 + self_width).min(other.west.0 + shift + other_width);
+            east > west
+        })
+    }
+    /// Returns the corners of this rectangle, clockwise from the northwest corner
+    fn corners(&self) -> [LatLon; 4] {
+        [
+            LatLon { latitude: self.north, longitude: self.west },
+            LatLon { latitude: self.north, longitude: self.east },
+            LatLon { latitude: self.south, longitude: self.east },
+            LatLon { latitude: self.south, longitude: self.west },
+        ]
+    }
 }
 
+/// The mean radius of the earth, in meters
+pub const EARTH_MEAN_RADIUS: f64 = 6_371_008.8;
+
+///
 /// Normalizes a latitude into the range [-90, 90]
+///
+/// A latitude outside that range describes a path that goes past a pole and continues on the
+/// other side of the earth, so the longitude of the point it describes is 180 degrees away.
+/// This only corrects the latitude. Use fold_latitude or normalize_latlon when the longitude
+/// also needs to be corrected.
+///
 pub fn normalize_latitude(latitude: Latitude) -> Latitude {
-    let radians = latitude.0.to_radians();
-    let result = f64::atan(f64::sin(radians) / f64::abs(f64::cos(radians)));
-    Latitude(result.to_degrees())
+    fold_latitude(latitude).0
+}
+///
+/// Folds a latitude over the poles into the range [-90, 90]
+///
+/// Returns the folded latitude, and true if the path went over a pole an odd number of times,
+/// so the longitude must be shifted by 180 degrees. For example, 100 degrees is 10 degrees past
+/// the north pole, which is 80 degrees north on the other side of the earth.
+///
+pub fn fold_latitude(latitude: Latitude) -> (Latitude, bool) {
+    let wrapped = (latitude.0 + 180.0).rem_euclid(360.0) - 180.0;
+    if wrapped > 90.0 {
+        (Latitude(180.0 - wrapped), true)
+    } else if wrapped < -90.0 {
+        (Latitude(-180.0 - wrapped), true)
+    } else {
+        (Latitude(wrapped), false)
+    }
+}
+///
+/// Normalizes a position so that its latitude is in the range [-90, 90] and its longitude
+/// is in the range [-180, 180]
+///
+/// If the latitude goes over a pole, the longitude is moved to the other side of the earth.
+///
+pub fn normalize_latlon(position: &LatLon) -> LatLon {
+    let (latitude, flip) = fold_latitude(position.latitude);
+    let longitude = if flip { position.longitude + Longitude(180.0) } else { position.longitude };
+    LatLon {
+        latitude,
+        longitude: normalize_longitude(longitude),
+    }
+}
+/// Returns true if two longitudes are within tolerance degrees of each other, going either way
+/// around the earth
+fn same_longitude(first: Longitude, second: Longitude, tolerance: f64) -> bool {
+    ((first.0 - second.0 + 180.0).rem_euclid(360.0) - 180.0).abs() <= tolerance
 }
 /// Normalizes a longitude into the range [-180, 180]
 pub fn normalize_longitude(longitude: Longitude) -> Longitude {
@@ -170,6 +547,7 @@ pub fn normalize_longitude(longitude: Longitude) -> Longitude {
 
 /// Stores a point
 #[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Point<N> {
     /// X coordinate
     pub x: N,
@@ -182,6 +560,79 @@ impl Point<f64> {
     pub fn origin() -> Point<f64> {
         Point { x: 0f64, y: 0f64 }
     }
+    /// Returns a point with the smaller of each coordinate of this point and another point
+    pub fn min(&self, other: &Point<f64>) -> Point<f64> {
+        Point { x: self.x.min(other.x), y: self.y.min(other.y) }
+    }
+    /// Returns a point with the larger of each coordinate of this point and another point
+    pub fn max(&self, other: &Point<f64>) -> Point<f64> {
+        Point { x: self.x.max(other.x), y: self.y.max(other.y) }
+    }
+    /// Returns a point with the absolute value of each coordinate of this point
+    pub fn abs(&self) -> Point<f64> {
+        Point { x: self.x.abs(), y: self.y.abs() }
+    }
+    /// Returns true if each coordinate of this point is within epsilon of the same coordinate
+    /// of another point
+    pub fn approx_eq(&self, other: &Point<f64>, epsilon: f64) -> bool {
+        (self.x - other.x).abs() <= epsilon && (self.y - other.y).abs() <= epsilon
+    }
+    /// Returns the distance from the origin to this point
+    pub fn magnitude(&self) -> f64 {
+        self.x.hypot(self.y)
+    }
+    /// Returns the distance from this point to another point
+    pub fn distance_to(&self, other: &Point<f64>) -> f64 {
+        (other.x - self.x).hypot(other.y - self.y)
+    }
+    /// Returns a point in the same direction from the origin with a magnitude of 1, or the
+    /// origin if this point is the origin
+    pub fn normalized(&self) -> Point<f64> {
+        let magnitude = self.magnitude();
+        if magnitude == 0.0 {
+            Point::origin()
+        } else {
+            Point { x: self.x / magnitude, y: self.y / magnitude }
+        }
+    }
+    /// Returns the dot product of this point and another point, treated as vectors
+    pub fn dot(&self, other: &Point<f64>) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+    ///
+    /// Returns the z component of the cross product of this point and another point, treated
+    /// as vectors
+    ///
+    /// This is positive if other is counterclockwise from this point, negative if it is
+    /// clockwise, and zero if they are parallel. Its absolute value is the area of the
+    /// parallelogram that the two vectors span.
+    ///
+    pub fn cross(&self, other: &Point<f64>) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+    /// Returns this point rotated counterclockwise about the origin by an angle in radians
+    pub fn rotate(&self, radians: f64) -> Point<f64> {
+        let (sin, cos) = radians.sin_cos();
+        Point {
+            x: self.x * cos - self.y * sin,
+            y: self.x * sin + self.y * cos,
+        }
+    }
+}
+
+impl Point<i32> {
+    /// Returns a point with the smaller of each coordinate of this point and another point
+    pub fn min(&self, other: &Point<i32>) -> Point<i32> {
+        Point { x: self.x.min(other.x), y: self.y.min(other.y) }
+    }
+    /// Returns a point with the larger of each coordinate of this point and another point
+    pub fn max(&self, other: &Point<i32>) -> Point<i32> {
+        Point { x: self.x.max(other.x), y: self.y.max(other.y) }
+    }
+    /// Returns a point with the absolute value of each coordinate of this point
+    pub fn abs(&self) -> Point<i32> {
+        Point { x: self.x.abs(), y: self.y.abs() }
+    }
 }
 
 impl<N> Add for Point<N> where N: Add<Output = N> {
@@ -211,30 +662,291 @@ impl<N> Mul<N> for Point<N> where N: Mul<N, Output = N> + Clone {
         }
     }
 }
+impl<N> Neg for Point<N> where N: Neg<Output = N> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Point {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// Implements multiplication of a Point by a scalar on the left, like `2.0 * point`
+macro_rules! impl_scalar_mul {
+    ($($scalar:ty),*) => {
+        $(
+            impl Mul<Point<$scalar>> for $scalar {
+                type Output = Point<$scalar>;
+                fn mul(self, rhs: Point<$scalar>) -> Point<$scalar> {
+                    rhs * self
+                }
+            }
+        )*
+    }
+}
+impl_scalar_mul!(f32, f64, i32, i64);
 
 ///
 /// A polygon
 ///
 /// P is the type used to represent a point
 ///
+/// With the serde feature, a polygon is serialized as a sequence of its points, in order.
+///
 #[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct Polygon<P> {
-    /// The points in this quadrilateral, in clockwise order
+    /// The points in this polygon, which should be in clockwise order
+    ///
+    /// The order is not checked when a polygon is created. Polygon::is_clockwise and
+    /// Polygon::reverse_winding can check and fix it.
     points: Vec<P>,
 }
 
 impl<P> Polygon<P> where P: Clone {
     pub fn new(points: &[P]) -> Polygon<P> {
         Polygon {
-            points: points.iter().cloned().collect(),
+            points: points.to_vec(),
         }
     }
 }
 
 impl<P> Polygon<P> {
-    pub fn points<'a>(&'a self) -> &'a [P] {
+    pub fn points(&self) -> &[P] {
         &self.points
     }
+    /// Reverses the order of the points in this polygon, which changes clockwise polygons to
+    /// counterclockwise and counterclockwise polygons to clockwise
+    pub fn reverse_winding(&mut self) {
+        self.points.reverse()
+    }
+}
+
+impl Polygon<LatLon> {
+    ///
+    /// Fixes common problems in polygons from other sources, and returns a report of what
+    /// was changed
+    ///
+    /// This removes each point that is the same as the point before it, reverses the points if
+    /// they go counterclockwise (with north up and east to the right, across the shorter way
+    /// around the earth between consecutive points), and closes the ring by adding a copy of the
+    /// first point at the end if the last point is not already the same as the first point.
+    ///
+    pub fn repair(&mut self) -> RepairReport {
+        let mut report = RepairReport::default();
+        let count = self.points.len();
+        self.points.dedup();
+        report.duplicates_removed = count - self.points.len();
+
+        // The winding comes from the area in longitude and latitude, with each longitude moved
+        // to within 180 degrees of the one before it
+        let first = match self.points.first() {
+            Some(first) => Point { x: first.longitude.0, y: first.latitude.0 },
+            None => return report,
+        };
+        let mut longitude = first.x;
+        let rest = self.points.windows(2).map(|pair| {
+            longitude += normalize_longitude(pair[1].longitude - pair[0].longitude).0;
+            Point { x: longitude, y: pair[1].latitude.0 }
+        });
+        let flat: Polygon<Point<f64>> = Some(first).into_iter().chain(rest).collect();
+        if flat.signed_area() > 0.0 {
+            self.reverse_winding();
+            report.reversed = true;
+        }
+
+        if self.points.len() > 1 && self.points.first() != self.points.last() {
+            let first = self.points[0].clone();
+            self.points.push(first);
+            report.closed = true;
+        }
+        report
+    }
+
+    ///
+    /// Returns the smallest rectangle that contains all points of this polygon, or None if
+    /// this polygon has no points
+    ///
+    /// The rectangle goes from the smallest to the largest latitude and longitude of the points.
+    /// It never crosses the antimeridian, so a polygon that crosses the antimeridian gets
+    /// a rectangle that goes the long way around the earth, covering every longitude between
+    /// its westernmost and easternmost points.
+    ///
+    pub fn bounds(&self) -> Option<LatLonRect> {
+        LatLonRect::from_points(self.points.iter().cloned())
+    }
+
+    ///
+    /// Returns the area-weighted centroid of this polygon on the surface of the sphere,
+    /// or None if this polygon has fewer than three points or encloses no area
+    ///
+    /// Unlike an average of latitudes and longitudes, this accounts for the area of each part of
+    /// the polygon on the sphere. It is the direction of the centroid of the enclosed surface.
+    /// Of the two regions that the polygon divides the sphere into, this uses the one on the
+    /// same side as the polygon's vertices.
+    ///
+    pub fn spherical_centroid(&self) -> Option<LatLon> {
+        if self.points.len() < 3 {
+            return None;
+        }
+        let vertices: Vec<Vector3> = self.points.iter().map(Vector3::from_latlon).collect();
+        // The integral of position over the enclosed surface is half the sum, over all edges,
+        // of the edge's unit normal vector weighted by the edge's arc length
+        let mut integral = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+        let mut vertex_sum = Vector3 { x: 0.0, y: 0.0, z: 0.0 };
+        for (i, &start) in vertices.iter().enumerate() {
+            let end = vertices[(i + 1) % vertices.len()];
+            let normal = start.cross(end);
+            let normal_magnitude = normal.magnitude();
+            if normal_magnitude > 0.0 {
+                integral = integral + normal * (start.angle_to(end) / normal_magnitude);
+            }
+            vertex_sum = vertex_sum + start;
+        }
+        if integral.magnitude() < 1e-12 {
+            return None;
+        }
+        // Clockwise vertices produce the centroid of the region on the other side
+        if integral.dot(vertex_sum) < 0.0 {
+            integral = integral * -1.0;
+        }
+        Some(integral.to_latlon())
+    }
+}
+
+impl Polygon<Point<f64>> {
+    ///
+    /// Returns the area of this polygon, which is positive if its points go counterclockwise
+    /// and negative if they go clockwise
+    ///
+    /// Counterclockwise is in a coordinate system where Y increases going up, like map
+    /// coordinates. The area of a self-intersecting polygon is the sum of the areas of its parts,
+    /// with opposite signs for parts that wind in opposite directions.
+    ///
+    pub fn signed_area(&self) -> f64 {
+        // Shoelace formula
+        let twice_area: f64 = self.points.iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .map(|(start, end)| start.x * end.y - end.x * start.y)
+            .sum();
+        twice_area / 2.0
+    }
+
+    ///
+    /// Returns true if the points of this polygon go clockwise, in a coordinate system where
+    /// Y increases going up
+    ///
+    /// A polygon with no area is not clockwise.
+    ///
+    pub fn is_clockwise(&self) -> bool {
+        self.signed_area() < 0.0
+    }
+
+    ///
+    /// Returns the centroid of the area of this polygon
+    ///
+    /// If this polygon has no area, this returns the average of its points instead. If it has
+    /// no points, both coordinates are NaN.
+    ///
+    pub fn centroid(&self) -> Point<f64> {
+        let area = self.signed_area();
+        if area == 0.0 {
+            let sum = self.points.iter().fold(Point::origin(), |sum, point| sum + point.clone());
+            return sum * (1.0 / self.points.len() as f64);
+        }
+        let sum = self.points.iter()
+            .zip(self.points.iter().cycle().skip(1))
+            .fold(Point::origin(), |sum, (start, end)| {
+                let cross = start.x * end.y - end.x * start.y;
+                sum + (start.clone() + end.clone()) * cross
+            });
+        sum * (1.0 / (6.0 * area))
+    }
+
+    ///
+    /// Returns true if a point is inside this polygon, using the even-odd rule
+    ///
+    /// The polygon is closed by an edge from its last point to its first point. A point counts as
+    /// inside if a ray from it in the positive X direction crosses the edges an odd number of
+    /// times, so points inside areas that a self-intersecting polygon overlaps an even number of
+    /// times are outside.
+    ///
+    /// Points exactly on an edge are inside if the polygon's interior is on the point's positive
+    /// X side, or for horizontal edges, on its positive Y side. Vertices follow the same rule
+    /// for the edges next to them. This way, a point on an edge that two polygons share is
+    /// inside exactly one of them.
+    ///
+    pub fn contains(&self, point: &Point<f64>) -> bool {
+        let mut inside = false;
+        for (i, start) in self.points.iter().enumerate() {
+            let end = &self.points[(i + 1) % self.points.len()];
+            if (start.y > point.y) != (end.y > point.y) {
+                let crossing_x = start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x);
+                if point.x < crossing_x {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+    ///
+    /// Returns the endpoints of the longest axis of this polygon, or None if this polygon has no
+    /// points or all of its points are the same
+    ///
+    /// The axis passes through the mean of the vertices in the direction along which the vertices
+    /// vary the most (the principal component of their covariance). The endpoints are the
+    /// projections of the outermost vertices onto that line.
+    ///
+    pub fn principal_axis(&self) -> Option<(Point<f64>, Point<f64>)> {
+        if self.points.is_empty() {
+            return None;
+        }
+        let count = self.points.len() as f64;
+        let sum = self.points.iter().fold(Point::origin(), |sum, point| sum + point.clone());
+        let mean = sum * (1.0 / count);
+
+        let (mut xx, mut yy, mut xy) = (0.0, 0.0, 0.0);
+        for point in self.points.iter() {
+            let offset = point.clone() - mean.clone();
+            xx += offset.x * offset.x;
+            yy += offset.y * offset.y;
+            xy += offset.x * offset.y;
+        }
+        if xx + yy == 0.0 {
+            return None;
+        }
+        // Direction of the eigenvector with the larger eigenvalue
+        let angle = 0.5 * f64::atan2(2.0 * xy, xx - yy);
+        let direction = Point { x: angle.cos(), y: angle.sin() };
+
+        let (min, max) = self.points.iter()
+            .map(|point| {
+                let offset = point.clone() - mean.clone();
+                offset.x * direction.x + offset.y * direction.y
+            })
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), distance| (min.min(distance), max.max(distance)));
+        Some((mean.clone() + direction.clone() * min, mean + direction * max))
+    }
+}
+
+/// The changes that Polygon::repair made to a polygon
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct RepairReport {
+    /// The number of points that were removed because they were the same as the point before
+    pub duplicates_removed: usize,
+    /// True if the order of the points was reversed to make them go clockwise
+    pub reversed: bool,
+    /// True if a copy of the first point was added at the end to close the ring
+    pub closed: bool,
+}
+
+impl RepairReport {
+    /// Returns true if the polygon was changed
+    pub fn changed(&self) -> bool {
+        self.duplicates_removed != 0 || self.reversed || self.closed
+    }
 }
 
 impl<P> FromIterator<P> for Polygon<P> {
@@ -247,46 +959,762 @@ impl<P> FromIterator<P> for Polygon<P> {
 
 #[test]
 fn test_normalize_lat_identity_1() {
-    assert_eq!(10.0, normalize_latitude(10.0));
+    assert_eq!(Latitude(10.0), normalize_latitude(Latitude(10.0)));
 }
 #[test]
 fn test_normalize_lat_identity_2() {
-    assert_eq!(90.0, normalize_latitude(90.0));
+    assert_eq!(Latitude(90.0), normalize_latitude(Latitude(90.0)));
 }
 #[test]
 fn test_normalize_lon_identity_1() {
-    assert_eq!(10.0, normalize_longitude(10.0));
+    assert_eq!(Longitude(10.0), normalize_longitude(Longitude(10.0)));
 }
 #[test]
 fn test_normalize_lon_identity_2() {
-    assert_eq!(90.0, normalize_longitude(90.0));
+    assert_eq!(Longitude(90.0), normalize_longitude(Longitude(90.0)));
+}
+#[test]
+fn test_is_adjacent_east_west() {
+    let tile = LatLonRect::from_bounds(Latitude(10.0), Latitude(0.0), Longitude(10.0), Longitude(0.0));
+    let east = LatLonRect::from_bounds(Latitude(10.0), Latitude(0.0), Longitude(20.0), Longitude(10.0));
+    assert_eq!(Some(Edge::East), tile.is_adjacent(&east, 1e-9));
+    assert_eq!(Some(Edge::West), east.is_adjacent(&tile, 1e-9));
+}
+#[test]
+fn test_is_adjacent_north_south() {
+    let tile = LatLonRect::from_bounds(Latitude(10.0), Latitude(0.0), Longitude(10.0), Longitude(0.0));
+    let north = LatLonRect::from_bounds(Latitude(20.0), Latitude(10.0001), Longitude(15.0), Longitude(5.0));
+    assert_eq!(None, tile.is_adjacent(&north, 1e-9));
+    assert_eq!(Some(Edge::North), tile.is_adjacent(&north, 0.001));
+    assert_eq!(Some(Edge::South), north.is_adjacent(&tile, 0.001));
+}
+#[test]
+fn test_is_adjacent_antimeridian() {
+    let west_of = LatLonRect::from_bounds(Latitude(10.0), Latitude(0.0), Longitude(180.0), Longitude(170.0));
+    let east_of = LatLonRect::from_bounds(Latitude(10.0), Latitude(0.0), Longitude(-170.0), Longitude(-180.0));
+    assert_eq!(Some(Edge::East), west_of.is_adjacent(&east_of, 1e-9));
+    // A rectangle crossing the antimeridian shares its north edge with one on the other side
+    let crossing = LatLonRect::from_bounds(Latitude(0.0), Latitude(-10.0), Longitude(-175.0), Longitude(175.0));
+    assert_eq!(Some(Edge::North), crossing.is_adjacent(&east_of, 1e-9));
+}
+#[test]
+fn test_is_adjacent_overlap_and_corner() {
+    let tile = LatLonRect::from_bounds(Latitude(10.0), Latitude(0.0), Longitude(10.0), Longitude(0.0));
+    let overlapping = LatLonRect::from_bounds(Latitude(15.0), Latitude(5.0), Longitude(15.0), Longitude(5.0));
+    let corner = LatLonRect::from_bounds(Latitude(20.0), Latitude(10.0), Longitude(20.0), Longitude(10.0));
+    assert_eq!(None, tile.is_adjacent(&overlapping, 1e-9));
+    assert_eq!(None, tile.is_adjacent(&corner, 1e-9));
+}
+#[test]
+fn test_distance_seattle_palo_alto() {
+    let seattle = LatLon { latitude: Latitude(47.6062), longitude: Longitude(-122.3321) };
+    let palo_alto = LatLon { latitude: Latitude(37.4419), longitude: Longitude(-122.1430) };
+    let distance = seattle.distance_to_earth(&palo_alto);
+    assert!((distance / 1_130_000.0 - 1.0).abs() < 0.005);
+    assert!(close_enough(distance / EARTH_MEAN_RADIUS, palo_alto.distance_to(&seattle, 1.0)));
+}
+#[test]
+fn test_distance_identical_and_antipodal() {
+    let point = LatLon { latitude: Latitude(47.6062), longitude: Longitude(-122.3321) };
+    assert_eq!(0.0, point.distance_to(&point, 1.0));
+    let antipodal = point.distance_to(&point.antipode(), 1.0);
+    assert!(antipodal.is_finite());
+    assert!(close_enough(std::f64::consts::PI, antipodal));
+    let poles = LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) }
+        .distance_to(&LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) }, 1.0);
+    assert!(close_enough(std::f64::consts::PI, poles));
+}
+#[test]
+fn test_bearing_to_cardinal() {
+    let start = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+    let north = LatLon { latitude: Latitude(30.0), longitude: Longitude(20.0) };
+    let south = LatLon { latitude: Latitude(-30.0), longitude: Longitude(20.0) };
+    assert_eq!(0.0, start.bearing_to(&north));
+    assert!(close_enough(180.0, start.bearing_to(&south)));
+    let equator = LatLon { latitude: Latitude(0.0), longitude: Longitude(20.0) };
+    let east = LatLon { latitude: Latitude(0.0), longitude: Longitude(40.0) };
+    let west = LatLon { latitude: Latitude(0.0), longitude: Longitude(-10.0) };
+    assert!(close_enough(90.0, equator.bearing_to(&east)));
+    assert!(close_enough(270.0, equator.bearing_to(&west)));
+}
+#[test]
+fn test_bearing_to_oblique() {
+    let seattle = LatLon { latitude: Latitude(47.6062), longitude: Longitude(-122.3321) };
+    let palo_alto = LatLon { latitude: Latitude(37.4419), longitude: Longitude(-122.1430) };
+    let bearing = seattle.bearing_to(&palo_alto);
+    assert!((bearing - 179.1).abs() < 0.1);
+    // The path from Palo Alto leaves toward the north, slightly west
+    let back = palo_alto.bearing_to(&seattle);
+    assert!(back > 359.0 && back < 360.0);
+}
+#[test]
+fn test_destination_round_trip() {
+    let seattle = LatLon { latitude: Latitude(47.6062), longitude: Longitude(-122.3321) };
+    for &bearing in [0.0, 45.0, 135.0, 200.0, 300.0].iter() {
+        let end = seattle.destination(bearing, 2_500_000.0, EARTH_MEAN_RADIUS);
+        assert!((seattle.distance_to_earth(&end) - 2_500_000.0).abs() < 1.0);
+        assert!((seattle.bearing_to(&end) - bearing).abs() < 1e-6);
+    }
+}
+#[test]
+fn test_destination_to_pole() {
+    let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(30.0) };
+    let quarter = std::f64::consts::PI / 2.0 * EARTH_MEAN_RADIUS;
+    let end = start.destination(0.0, quarter, EARTH_MEAN_RADIUS);
+    assert!(close_enough(90.0, end.latitude.0));
+}
+#[test]
+fn test_destination_antimeridian() {
+    let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(170.0) };
+    let end = start.destination(90.0, 20f64.to_radians(), 1.0);
+    assert!(close_enough(0.0, end.latitude.0));
+    assert!(close_enough(-170.0, end.longitude.0));
+}
+#[test]
+fn test_interpolate_midpoint() {
+    let seattle = LatLon { latitude: Latitude(47.6062), longitude: Longitude(-122.3321) };
+    let tokyo = LatLon { latitude: Latitude(35.6762), longitude: Longitude(139.6503) };
+    let middle = seattle.interpolate(&tokyo, 0.5);
+    assert!(close_enough(seattle.distance_to(&middle, 1.0), tokyo.distance_to(&middle, 1.0)));
+    assert!(close_enough(seattle.distance_to(&tokyo, 1.0) / 2.0, seattle.distance_to(&middle, 1.0)));
+    // On the great circle, the midpoint is in the plane through both points and the center
+    let normal = Vector3::from_latlon(&seattle).cross(Vector3::from_latlon(&tokyo));
+    assert!(Vector3::from_latlon(&middle).dot(normal).abs() < 1e-9);
+    // The great circle goes north of both cities
+    assert!(middle.latitude.0 > 50.0);
+}
+#[test]
+fn test_interpolate_endpoints() {
+    let start = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+    let end = LatLon { latitude: Latitude(-30.0), longitude: Longitude(60.0) };
+    let at_start = start.interpolate(&end, 0.0);
+    let at_end = start.interpolate(&end, 1.0);
+    assert!(close_enough(10.0, at_start.latitude.0));
+    assert!(close_enough(20.0, at_start.longitude.0));
+    assert!(close_enough(-30.0, at_end.latitude.0));
+    assert!(close_enough(60.0, at_end.longitude.0));
+    assert_eq!(start, start.interpolate(&start, 0.5));
+}
+#[test]
+fn test_interpolate_antipodal() {
+    let start = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+    let quarter = start.interpolate(&start.antipode(), 0.25);
+    assert!(quarter.latitude.0.is_finite() && quarter.longitude.0.is_finite());
+    assert!(close_enough(std::f64::consts::PI / 4.0, start.distance_to(&quarter, 1.0)));
+    let pole = LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) };
+    let middle = pole.interpolate(&pole.antipode(), 0.5);
+    assert!(close_enough(0.0, middle.latitude.0));
+}
+#[test]
+fn test_contains_square() {
+    let square = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 0.0, y: 2.0 },
+        Point { x: 2.0, y: 2.0 },
+        Point { x: 2.0, y: 0.0 },
+    ]);
+    assert!(square.contains(&Point { x: 1.0, y: 1.0 }));
+    assert!(square.contains(&Point { x: 0.1, y: 1.9 }));
+    assert!(!square.contains(&Point { x: 3.0, y: 1.0 }));
+    assert!(!square.contains(&Point { x: -1.0, y: 1.0 }));
+    assert!(!square.contains(&Point { x: 1.0, y: -0.5 }));
+}
+#[test]
+fn test_contains_l_shape() {
+    let l_shape = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 0.0, y: 3.0 },
+        Point { x: 1.0, y: 3.0 },
+        Point { x: 1.0, y: 1.0 },
+        Point { x: 3.0, y: 1.0 },
+        Point { x: 3.0, y: 0.0 },
+    ]);
+    assert!(l_shape.contains(&Point { x: 0.5, y: 2.5 }));
+    assert!(l_shape.contains(&Point { x: 2.5, y: 0.5 }));
+    // The notch of the L
+    assert!(!l_shape.contains(&Point { x: 2.0, y: 2.0 }));
+}
+#[test]
+fn test_contains_shared_edge() {
+    // A point on the edge between two squares is in exactly one of them
+    let left = Polygon::new(&[Point { x: 0.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }, Point { x: 1.0, y: 1.0 }, Point { x: 1.0, y: 0.0 }]);
+    let right = Polygon::new(&[Point { x: 1.0, y: 0.0 }, Point { x: 1.0, y: 1.0 }, Point { x: 2.0, y: 1.0 }, Point { x: 2.0, y: 0.0 }]);
+    let on_edge = Point { x: 1.0, y: 0.5 };
+    assert!(left.contains(&on_edge) != right.contains(&on_edge));
+}
+#[test]
+fn test_unit_square_area_centroid() {
+    let square = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 1.0, y: 0.0 },
+        Point { x: 1.0, y: 1.0 },
+        Point { x: 0.0, y: 1.0 },
+    ]);
+    assert_eq!(1.0, square.signed_area());
+    assert_eq!(Point { x: 0.5, y: 0.5 }, square.centroid());
+    let clockwise: Polygon<Point<f64>> = square.points().iter().rev().cloned().collect();
+    assert_eq!(-1.0, clockwise.signed_area());
+    assert_eq!(Point { x: 0.5, y: 0.5 }, clockwise.centroid());
+}
+#[test]
+fn test_triangle_centroid() {
+    let triangle = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 6.0, y: 0.0 },
+        Point { x: 0.0, y: 3.0 },
+    ]);
+    assert!(close_enough(9.0, triangle.signed_area()));
+    let centroid = triangle.centroid();
+    assert!(close_enough(2.0, centroid.x));
+    assert!(close_enough(1.0, centroid.y));
+}
+#[test]
+fn test_degenerate_centroid() {
+    let line = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 1.0, y: 1.0 },
+        Point { x: 5.0, y: 5.0 },
+    ]);
+    assert_eq!(0.0, line.signed_area());
+    assert_eq!(Point { x: 2.0, y: 2.0 }, line.centroid());
+}
+#[test]
+fn test_repair() {
+    let ll = |latitude: f64, longitude: f64| LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+    // Counterclockwise, open, and with repeated points
+    let mut polygon = Polygon::new(&[
+        ll(0.0, 0.0),
+        ll(0.0, 0.0),
+        ll(0.0, 10.0),
+        ll(10.0, 10.0),
+        ll(10.0, 10.0),
+        ll(10.0, 10.0),
+        ll(10.0, 0.0),
+    ]);
+    let report = polygon.repair();
+    assert_eq!(RepairReport { duplicates_removed: 3, reversed: true, closed: true }, report);
+    assert!(report.changed());
+    assert_eq!(&[ll(10.0, 0.0), ll(10.0, 10.0), ll(0.0, 10.0), ll(0.0, 0.0), ll(10.0, 0.0)], polygon.points());
+    // Repairing again changes nothing
+    assert!(!polygon.repair().changed());
+}
+#[test]
+fn test_repair_triangle_winding() {
+    let ll = |latitude: f64, longitude: f64| LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+    // Without the first point, the rest of this counterclockwise triangle has no area
+    let mut polygon = Polygon::new(&[ll(0.0, 0.0), ll(0.0, 10.0), ll(10.0, 5.0)]);
+    assert!(polygon.repair().reversed);
+}
+#[test]
+fn test_repair_across_antimeridian() {
+    let ll = |latitude: f64, longitude: f64| LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+    // Clockwise, going east across the antimeridian along the north edge
+    let mut polygon = Polygon::new(&[ll(10.0, 170.0), ll(10.0, -170.0), ll(0.0, -170.0), ll(0.0, 170.0), ll(10.0, 170.0)]);
+    assert!(!polygon.repair().changed());
+}
+#[test]
+fn test_winding() {
+    let mut square = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 1.0, y: 0.0 },
+        Point { x: 1.0, y: 1.0 },
+        Point { x: 0.0, y: 1.0 },
+    ]);
+    let original = square.clone();
+    assert!(!square.is_clockwise());
+    square.reverse_winding();
+    assert!(square.is_clockwise());
+    square.reverse_winding();
+    assert_eq!(original, square);
+}
+#[test]
+fn test_polygon_bounds() {
+    // Around Palo Alto
+    let polygon = Polygon::new(&[
+        LatLon { latitude: Latitude(37.47), longitude: Longitude(-122.19) },
+        LatLon { latitude: Latitude(37.46), longitude: Longitude(-122.10) },
+        LatLon { latitude: Latitude(37.37), longitude: Longitude(-122.09) },
+        LatLon { latitude: Latitude(37.36), longitude: Longitude(-122.21) },
+    ]);
+    let bounds = polygon.bounds().unwrap();
+    assert_eq!(Latitude(37.47), bounds.north());
+    assert_eq!(Latitude(37.36), bounds.south());
+    assert_eq!(Longitude(-122.09), bounds.east());
+    assert_eq!(Longitude(-122.21), bounds.west());
+}
+#[test]
+fn test_polygon_bounds_empty() {
+    let polygon: Polygon<LatLon> = Polygon::new(&[]);
+    assert!(polygon.bounds().is_none());
+}
+#[test]
+fn test_rect_contains_point() {
+    let rect = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    assert!(rect.contains_point(&LatLon { latitude: Latitude(35.0), longitude: Longitude(-115.0) }));
+    // Edges and corners
+    assert!(rect.contains_point(&LatLon { latitude: Latitude(40.0), longitude: Longitude(-115.0) }));
+    assert!(rect.contains_point(&LatLon { latitude: Latitude(35.0), longitude: Longitude(-120.0) }));
+    assert!(rect.contains_point(&LatLon { latitude: Latitude(30.0), longitude: Longitude(-110.0) }));
+    assert!(!rect.contains_point(&LatLon { latitude: Latitude(40.1), longitude: Longitude(-115.0) }));
+    assert!(!rect.contains_point(&LatLon { latitude: Latitude(35.0), longitude: Longitude(-109.9) }));
+}
+#[test]
+fn test_rect_contains_rect() {
+    let rect = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    let inside = LatLonRect::from_bounds(Latitude(38.0), Latitude(32.0), Longitude(-112.0), Longitude(-118.0));
+    let overlapping = LatLonRect::from_bounds(Latitude(45.0), Latitude(35.0), Longitude(-112.0), Longitude(-118.0));
+    assert!(rect.contains_rect(&inside));
+    assert!(!inside.contains_rect(&rect));
+    assert!(!rect.contains_rect(&overlapping));
+    assert!(rect.contains_rect(&rect));
+}
+#[test]
+fn test_rect_intersection_overlapping() {
+    let a = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    let b = LatLonRect::from_bounds(Latitude(45.0), Latitude(35.0), Longitude(-100.0), Longitude(-115.0));
+    let expected = LatLonRect::from_bounds(Latitude(40.0), Latitude(35.0), Longitude(-110.0), Longitude(-115.0));
+    assert_eq!(Some(expected.clone()), a.intersection(&b));
+    assert_eq!(Some(expected), b.intersection(&a));
+}
+#[test]
+fn test_rect_intersection_disjoint() {
+    let a = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    let beside = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-100.0), Longitude(-105.0));
+    let above = LatLonRect::from_bounds(Latitude(50.0), Latitude(45.0), Longitude(-110.0), Longitude(-120.0));
+    assert_eq!(None, a.intersection(&beside));
+    assert_eq!(None, a.intersection(&above));
+}
+#[test]
+fn test_rect_intersection_touching() {
+    let a = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    let b = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-100.0), Longitude(-110.0));
+    let intersection = a.intersection(&b).unwrap();
+    assert!(intersection.is_empty());
+    assert_eq!(Longitude(-110.0), intersection.east());
+    assert_eq!(Longitude(-110.0), intersection.west());
+}
+#[test]
+fn test_rect_union() {
+    let a = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    let b = LatLonRect::from_bounds(Latitude(20.0), Latitude(10.0), Longitude(-80.0), Longitude(-90.0));
+    let expected = LatLonRect::from_bounds(Latitude(40.0), Latitude(10.0), Longitude(-80.0), Longitude(-120.0));
+    assert_eq!(expected, a.union(&b));
+    assert!(a.union(&b).contains_rect(&a));
+    assert!(a.union(&b).contains_rect(&b));
+}
+#[test]
+fn test_rect_center() {
+    let rect = LatLonRect::from_bounds(Latitude(10.0), Latitude(-10.0), Longitude(20.0), Longitude(-20.0));
+    assert_eq!(LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) }, rect.center());
+    let rect = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    assert_eq!(LatLon { latitude: Latitude(35.0), longitude: Longitude(-115.0) }, rect.center());
+}
+#[test]
+fn test_rect_expand_to_include() {
+    let mut rect = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+    rect.expand_to_include(&LatLon { latitude: Latitude(35.0), longitude: Longitude(-100.0) });
+    assert_eq!(LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-100.0), Longitude(-120.0)), rect);
+    // A point that is already inside does not change the rectangle
+    rect.expand_to_include(&LatLon { latitude: Latitude(32.0), longitude: Longitude(-115.0) });
+    assert_eq!(LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-100.0), Longitude(-120.0)), rect);
+    rect.expand_to_include(&LatLon { latitude: Latitude(20.0), longitude: Longitude(-130.0) });
+    assert_eq!(LatLonRect::from_bounds(Latitude(40.0), Latitude(20.0), Longitude(-100.0), Longitude(-130.0)), rect);
+}
+#[test]
+fn test_rect_from_points_single() {
+    let point = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+    let rect = LatLonRect::from_points(Some(point.clone())).unwrap();
+    assert!(rect.is_point());
+    assert_eq!(point, rect.center());
+}
+#[test]
+fn test_rect_from_points_multiple() {
+    let track = [
+        LatLon { latitude: Latitude(47.66), longitude: Longitude(-122.28) },
+        LatLon { latitude: Latitude(47.70), longitude: Longitude(-122.30) },
+        LatLon { latitude: Latitude(47.62), longitude: Longitude(-122.25) },
+    ];
+    let rect = LatLonRect::from_points(track.iter().cloned()).unwrap();
+    assert_eq!(LatLonRect::from_bounds(Latitude(47.70), Latitude(47.62), Longitude(-122.25), Longitude(-122.30)), rect);
+}
+#[test]
+fn test_rect_from_points_empty() {
+    assert_eq!(None, LatLonRect::from_points(Vec::new()));
+}
+#[test]
+fn test_point_distance() {
+    let a = Point { x: 1.0, y: 2.0 };
+    let b = Point { x: 4.0, y: 6.0 };
+    assert_eq!(5.0, a.distance_to(&b));
+    assert_eq!(5.0, b.distance_to(&a));
+    assert_eq!(0.0, a.distance_to(&a));
+    assert_eq!(5.0, Point { x: -3.0, y: 4.0 }.magnitude());
+}
+#[test]
+fn test_point_normalized() {
+    let unit = Point { x: 3.0, y: -4.0 }.normalized();
+    assert!(close_enough(1.0, unit.magnitude()));
+    assert!(close_enough(0.6, unit.x));
+    assert!(close_enough(-0.8, unit.y));
+    let zero = Point::origin().normalized();
+    assert!(!zero.x.is_nan() && !zero.y.is_nan());
+    assert_eq!(Point::origin(), zero);
+}
+#[test]
+fn test_point_dot() {
+    let x = Point { x: 1.0, y: 0.0 };
+    let y = Point { x: 0.0, y: 1.0 };
+    assert_eq!(0.0, x.dot(&y));
+    assert_eq!(1.0, x.dot(&x));
+    assert_eq!(11.0, Point { x: 1.0, y: 2.0 }.dot(&Point { x: 3.0, y: 4.0 }));
+}
+#[test]
+fn test_point_cross() {
+    let a = Point { x: 2.0, y: 0.0 };
+    let b = Point { x: 1.0, y: 3.0 };
+    assert_eq!(6.0, a.cross(&b));
+    assert_eq!(-6.0, b.cross(&a));
+    assert_eq!(0.0, a.cross(&Point { x: -4.0, y: 0.0 }));
+}
+#[test]
+fn test_point_rotate() {
+    use std::f64::consts::{FRAC_PI_2, PI};
+    let rotated = Point { x: 1.0, y: 0.0 }.rotate(FRAC_PI_2);
+    assert!(close_enough(0.0, rotated.x));
+    assert!(close_enough(1.0, rotated.y));
+    let rotated = Point { x: 1.0, y: 2.0 }.rotate(PI);
+    assert!(close_enough(-1.0, rotated.x));
+    assert!(close_enough(-2.0, rotated.y));
+    let rotated = Point { x: 0.0, y: 1.0 }.rotate(-FRAC_PI_2);
+    assert!(close_enough(1.0, rotated.x));
+    assert!(close_enough(0.0, rotated.y));
+}
+#[test]
+fn test_point_neg() {
+    assert_eq!(Point { x: -1, y: 2 }, -Point { x: 1, y: -2 });
+    assert_eq!(Point { x: -1.5, y: 0.0 }, -Point { x: 1.5, y: -0.0 });
+}
+#[test]
+fn test_point_scalar_mul_left() {
+    let p = Point { x: 1.5, y: -3.0 };
+    assert_eq!(p.clone() * 2.0, 2.0 * p.clone());
+    assert_eq!(Point { x: 3.0, y: -6.0 }, 2.0 * p);
+    assert_eq!(Point { x: -3, y: 12 }, -3 * Point { x: 1, y: -4 });
+}
+#[test]
+fn test_latlon_approx_eq() {
+    let a = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+    let b = LatLon { latitude: Latitude(47.6609 + 1e-12), longitude: Longitude(-122.2816 - 1e-12) };
+    assert!(a.approx_eq(&b, 1e-9));
+    assert!(b.approx_eq(&a, 1e-9));
+    let c = LatLon { latitude: Latitude(48.6609), longitude: Longitude(-122.2816) };
+    assert!(!a.approx_eq(&c, 1e-9));
+    let d = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-121.2816) };
+    assert!(!a.approx_eq(&d, 1e-9));
+}
+#[test]
+fn test_point_approx_eq() {
+    let a = Point { x: 10.0, y: -3.0 };
+    assert!(a.approx_eq(&Point { x: 10.0 + 1e-12, y: -3.0 - 1e-12 }, 1e-9));
+    assert!(!a.approx_eq(&Point { x: 11.0, y: -3.0 }, 1e-9));
+    assert!(!a.approx_eq(&Point { x: 10.0, y: -2.0 }, 1e-9));
+    assert!(!a.approx_eq(&Point { x: f64::NAN, y: -3.0 }, 1e-9));
+}
+#[test]
+fn test_fold_latitude() {
+    assert_eq!((Latitude(89.0), true), fold_latitude(Latitude(91.0)));
+    assert_eq!((Latitude(0.0), true), fold_latitude(Latitude(180.0)));
+    assert_eq!((Latitude(-90.0), false), fold_latitude(Latitude(270.0)));
+    assert_eq!((Latitude(-45.0), true), fold_latitude(Latitude(-135.0)));
+    // Past the north pole and the far side of the equator
+    assert_eq!((Latitude(-10.0), true), fold_latitude(Latitude(190.0)));
+    assert_eq!((Latitude(30.0), false), fold_latitude(Latitude(390.0)));
+    assert_eq!((Latitude(-90.0), false), fold_latitude(Latitude(-90.0)));
+}
+#[test]
+fn test_normalize_latlon() {
+    let folded = normalize_latlon(&LatLon { latitude: Latitude(100.0), longitude: Longitude(30.0) });
+    assert_eq!(Latitude(80.0), folded.latitude);
+    assert!(close_enough(-150.0, folded.longitude.0));
+    let unchanged = normalize_latlon(&LatLon { latitude: Latitude(-20.0), longitude: Longitude(30.0) });
+    assert_eq!(Latitude(-20.0), unchanged.latitude);
+    assert!(close_enough(30.0, unchanged.longitude.0));
 }
 
 #[test]
 fn test_antipode_north_pole() {
-    let north_pole = LatLon { latitude: 90.0, longitude: 0.0 };
+    let north_pole = LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) };
     let antipode = north_pole.antipode();
-    assert!(close_enough(-90.0, antipode.latitude));
+    assert!(close_enough(-90.0, antipode.latitude.0));
 }
 #[test]
 fn test_antipode_south_pole() {
-    let south_pole = LatLon { latitude: -90.0, longitude: 0.0 };
+    let south_pole = LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) };
     let antipode = south_pole.antipode();
-    assert!(close_enough(90.0, antipode.latitude));
+    assert!(close_enough(90.0, antipode.latitude.0));
 }
 #[test]
 fn test_antipode_zero_zero() {
-    let point = LatLon { latitude: 0.0, longitude: 0.0 };
+    let point = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
     let antipode = point.antipode();
-    assert!(close_enough(0.0, antipode.latitude));
-    assert!(close_enough(180.0, antipode.longitude));
+    assert!(close_enough(0.0, antipode.latitude.0));
+    assert!(close_enough(180.0, antipode.longitude.0));
 }
 #[test]
 fn test_antipode_zero_90() {
-    let point = LatLon { latitude: 0.0, longitude: 90.0 };
+    let point = LatLon { latitude: Latitude(0.0), longitude: Longitude(90.0) };
     let antipode = point.antipode();
-    assert!(close_enough(0.0, antipode.latitude));
-    assert!(close_enough(-90.0, antipode.longitude));
+    assert!(close_enough(0.0, antipode.latitude.0));
+    assert!(close_enough(-90.0, antipode.longitude.0));
+}
+#[test]
+fn test_offset_to() {
+    let from = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+    let to = LatLon { latitude: Latitude(-5.0), longitude: Longitude(50.0) };
+    let (d_lat, d_lon) = from.offset_to(&to);
+    assert!(close_enough(-15.0, d_lat.0));
+    assert!(close_enough(30.0, d_lon.0));
+}
+#[test]
+fn test_offset_to_antimeridian() {
+    let from = LatLon { latitude: Latitude(0.0), longitude: Longitude(179.0) };
+    let to = LatLon { latitude: Latitude(0.0), longitude: Longitude(-179.0) };
+    let (_, east) = from.offset_to(&to);
+    let (_, west) = to.offset_to(&from);
+    assert!(close_enough(2.0, east.0));
+    assert!(close_enough(-2.0, west.0));
+}
+#[test]
+fn test_rotate_around_zero() {
+    let pivot = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+    let point = LatLon { latitude: Latitude(47.7), longitude: Longitude(-122.3) };
+    let rotated = point.rotate_around(&pivot, 0.0);
+    assert!(close_enough(point.latitude.0, rotated.latitude.0));
+    assert!(close_enough(point.longitude.0, rotated.longitude.0));
+}
+#[test]
+fn test_rotate_around_full_turn() {
+    let pivot = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+    let point = LatLon { latitude: Latitude(47.7), longitude: Longitude(-122.3) };
+    let rotated = point.rotate_around(&pivot, 360.0);
+    assert!(close_enough(point.latitude.0, rotated.latitude.0));
+    assert!(close_enough(point.longitude.0, rotated.longitude.0));
+}
+#[test]
+fn test_rotate_around_clockwise() {
+    // A point north of the pivot moves to the east of it
+    let pivot = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+    let point = LatLon { latitude: Latitude(10.0), longitude: Longitude(0.0) };
+    let rotated = point.rotate_around(&pivot, 90.0);
+    assert!(close_enough(0.0, rotated.latitude.0));
+    assert!(close_enough(10.0, rotated.longitude.0));
+}
+#[test]
+fn test_point_min_max_f64() {
+    let a = Point { x: -1.5, y: 2.0 };
+    let b = Point { x: 3.0, y: -4.25 };
+    assert_eq!(Point { x: -1.5, y: -4.25 }, a.min(&b));
+    assert_eq!(Point { x: 3.0, y: 2.0 }, a.max(&b));
+}
+#[test]
+fn test_point_abs_f64() {
+    assert_eq!(Point { x: 1.5, y: 4.25 }, Point { x: -1.5, y: -4.25 }.abs());
+}
+#[test]
+fn test_point_min_max_abs_i32() {
+    let a = Point { x: -7, y: 2 };
+    let b = Point { x: 3, y: -4 };
+    assert_eq!(Point { x: -7, y: -4 }, a.min(&b));
+    assert_eq!(Point { x: 3, y: 2 }, a.max(&b));
+    assert_eq!(Point { x: 7, y: 2 }, a.abs());
+}
+#[test]
+fn test_spherical_centroid_large_polygon() {
+    let polygon = Polygon::new(&[
+        LatLon { latitude: Latitude(80.0), longitude: Longitude(0.0) },
+        LatLon { latitude: Latitude(80.0), longitude: Longitude(90.0) },
+        LatLon { latitude: Latitude(0.0), longitude: Longitude(90.0) },
+        LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) },
+    ]);
+    // The planar centroid of this rectangle in latitude and longitude is at (40, 45), but more of
+    // its area on the sphere is near the equator. The edge along 80 N is a great circle arc
+    // that bends toward the pole, so this is not exactly a latitude/longitude rectangle.
+    let centroid = polygon.spherical_centroid().unwrap();
+    assert!(close_enough(45.0, centroid.longitude.0));
+    assert!(centroid.latitude.0 < 40.0 - 1.0);
+    assert!(centroid.latitude.0 > 0.0);
+}
+#[test]
+fn test_spherical_centroid_small_polygon() {
+    // A small polygon's centroid is close to the planar centroid, for either winding
+    let points = [
+        LatLon { latitude: Latitude(10.1), longitude: Longitude(19.9) },
+        LatLon { latitude: Latitude(10.1), longitude: Longitude(20.1) },
+        LatLon { latitude: Latitude(9.9), longitude: Longitude(20.1) },
+        LatLon { latitude: Latitude(9.9), longitude: Longitude(19.9) },
+    ];
+    let clockwise = Polygon::new(&points).spherical_centroid().unwrap();
+    let counterclockwise: Polygon<LatLon> = points.iter().rev().cloned().collect();
+    let counterclockwise = counterclockwise.spherical_centroid().unwrap();
+    for centroid in &[clockwise, counterclockwise] {
+        assert!(close_enough(10.0, centroid.latitude.0));
+        assert!(close_enough(20.0, centroid.longitude.0));
+    }
+}
+#[test]
+fn test_spherical_centroid_degenerate() {
+    let line = Polygon::new(&[
+        LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) },
+        LatLon { latitude: Latitude(1.0), longitude: Longitude(1.0) },
+    ]);
+    assert_eq!(None, line.spherical_centroid());
+    let repeated = Polygon::new(&[
+        LatLon { latitude: Latitude(5.0), longitude: Longitude(5.0) },
+        LatLon { latitude: Latitude(5.0), longitude: Longitude(5.0) },
+        LatLon { latitude: Latitude(5.0), longitude: Longitude(5.0) },
+    ]);
+    assert_eq!(None, repeated.spherical_centroid());
+}
+#[test]
+fn test_principal_axis_rectangle() {
+    let rectangle = Polygon::new(&[
+        Point { x: 0.0, y: 2.0 },
+        Point { x: 10.0, y: 2.0 },
+        Point { x: 10.0, y: 0.0 },
+        Point { x: 0.0, y: 0.0 },
+    ]);
+    let (start, end) = rectangle.principal_axis().unwrap();
+    assert!(close_enough(1.0, start.y) && close_enough(1.0, end.y));
+    assert!(close_enough(10.0, (end.x - start.x).abs()));
+}
+#[test]
+fn test_principal_axis_diagonal() {
+    let thin = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 5.0, y: 5.1 },
+        Point { x: 5.1, y: 5.0 },
+    ]);
+    let (start, end) = thin.principal_axis().unwrap();
+    let axis = end - start;
+    assert!(close_enough(axis.x.abs(), axis.y.abs()));
+}
+#[test]
+fn test_principal_axis_degenerate() {
+    let empty: Polygon<Point<f64>> = Polygon::new(&[]);
+    assert_eq!(None, empty.principal_axis());
+    let repeated = Polygon::new(&[Point { x: 3.0, y: 4.0 }, Point { x: 3.0, y: 4.0 }]);
+    assert_eq!(None, repeated.principal_axis());
+}
+#[test]
+fn test_lat_lon_rect_point() {
+    let point = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+    let rect = LatLonRect::from_corners(&point, &point);
+    assert!(rect.is_point());
+    assert!(rect.is_empty());
+}
+#[test]
+fn test_lat_lon_rect_line() {
+    let rect = LatLonRect::from_bounds(Latitude(10.0), Latitude(10.0), Longitude(20.0), Longitude(-20.0));
+    assert!(rect.is_empty());
+    assert!(!rect.is_point());
+}
+#[test]
+fn test_lat_lon_rect_not_empty() {
+    let rect = LatLonRect::from_bounds(Latitude(10.0), Latitude(-10.0), Longitude(20.0), Longitude(-20.0));
+    assert!(!rect.is_empty());
+    assert!(!rect.is_point());
+}
+
+#[test]
+fn test_snap_out() {
+    let rect = LatLonRect::from_bounds(Latitude(27.0), Latitude(3.0), Longitude(27.0), Longitude(3.0));
+    let expected = LatLonRect::from_bounds(Latitude(30.0), Latitude(0.0), Longitude(30.0), Longitude(0.0));
+    assert_eq!(expected, rect.snap_out(10.0, 10.0));
+}
+#[test]
+fn test_snap_out_already_aligned() {
+    let rect = LatLonRect::from_bounds(Latitude(-10.0), Latitude(-20.0), Longitude(-5.0), Longitude(-15.0));
+    assert_eq!(rect, rect.snap_out(10.0, 5.0));
+}
+#[test]
+fn test_snap_out_limits() {
+    let rect = LatLonRect::from_bounds(Latitude(85.0), Latitude(-85.0), Longitude(175.0), Longitude(-175.0));
+    let expected = LatLonRect::from_bounds(Latitude(90.0), Latitude(-90.0), Longitude(180.0), Longitude(-180.0));
+    assert_eq!(expected, rect.snap_out(30.0, 30.0));
+}
+#[test]
+fn test_snap_out_antimeridian() {
+    let rect = LatLonRect::from_bounds(Latitude(12.0), Latitude(-12.0), Longitude(-167.0), Longitude(173.0));
+    let expected = LatLonRect::from_bounds(Latitude(20.0), Latitude(-20.0), Longitude(-160.0), Longitude(170.0));
+    assert_eq!(expected, rect.snap_out(10.0, 10.0));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_polygon_latlon() {
+    let polygon = Polygon::new(&[
+        LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) },
+        LatLon { latitude: Latitude(-10.0), longitude: Longitude(20.0) },
+        LatLon { latitude: Latitude(-10.0), longitude: Longitude(-20.5) },
+    ]);
+    let json = serde_json::to_string(&polygon).unwrap();
+    assert_eq!(r#"[{"latitude":10.0,"longitude":20.0},{"latitude":-10.0,"longitude":20.0},{"latitude":-10.0,"longitude":-20.5}]"#, json);
+    assert_eq!(polygon, serde_json::from_str(&json).unwrap());
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_polygon_point() {
+    let polygon = Polygon::new(&[
+        Point { x: 0.0, y: 0.0 },
+        Point { x: 0.0, y: 1.0 },
+        Point { x: 1.0, y: 1.0 },
+        Point { x: 1.0, y: 0.0 },
+    ]);
+    let json = serde_json::to_string(&polygon).unwrap();
+    assert_eq!(r#"[{"x":0.0,"y":0.0},{"x":0.0,"y":1.0},{"x":1.0,"y":1.0},{"x":1.0,"y":0.0}]"#, json);
+    assert_eq!(polygon, serde_json::from_str(&json).unwrap());
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_polygon_keeps_order() {
+    // Counterclockwise points are deserialized in the same order, not reordered to be clockwise
+    let json = r#"[{"x":0.0,"y":0.0},{"x":1.0,"y":0.0},{"x":1.0,"y":1.0}]"#;
+    let polygon: Polygon<Point<f64>> = serde_json::from_str(json).unwrap();
+    assert_eq!(&[Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 0.0 }, Point { x: 1.0, y: 1.0 }], polygon.points());
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_latitude_longitude() {
+    assert_eq!("47.5", serde_json::to_string(&Latitude(47.5)).unwrap());
+    assert_eq!("-122.25", serde_json::to_string(&Longitude(-122.25)).unwrap());
+    assert_eq!(Latitude(47.5), serde_json::from_str("47.5").unwrap());
+    assert_eq!(Longitude(-122.25), serde_json::from_str("-122.25").unwrap());
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_latlon() {
+    let ll = LatLon { latitude: Latitude(47.5), longitude: Longitude(-122.25) };
+    let json = serde_json::to_string(&ll).unwrap();
+    assert_eq!(r#"{"latitude":47.5,"longitude":-122.25}"#, json);
+    assert_eq!(ll, serde_json::from_str(&json).unwrap());
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_point() {
+    let point = Point { x: 1.5, y: -2.0 };
+    let json = serde_json::to_string(&point).unwrap();
+    assert_eq!(r#"{"x":1.5,"y":-2.0}"#, json);
+    assert_eq!(point, serde_json::from_str(&json).unwrap());
+    let pixel = Point { x: 3, y: 4 };
+    assert_eq!(pixel, serde_json::from_str::<Point<i32>>(&serde_json::to_string(&pixel).unwrap()).unwrap());
+}
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde_latlonrect() {
+    let rect = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.5));
+    let json = serde_json::to_string(&rect).unwrap();
+    assert_eq!(r#"{"north":40.0,"south":30.0,"east":-110.0,"west":-120.5}"#, json);
+    assert_eq!(rect, serde_json::from_str(&json).unwrap());
 }
 
 #[cfg(test)]
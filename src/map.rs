@@ -1,6 +1,44 @@
-use super::{Point, LatLon};
+use std::f64::consts::PI;
+
+#[cfg(feature = "serde")]
+use serde::{Serialize, Deserialize};
+
+use super::{Point, Latitude, Longitude, LatLon, LatLonRect, Polygon, EARTH_MEAN_RADIUS, normalize_latlon, normalize_longitude};
 use projection::Projection;
-use layer::Layer;
+use layer::{self, Layer};
+use canvas::Canvas;
+use geometry;
+use sphere::Vector3;
+
+/// The graticule spacings, in degrees, that auto_graticule_spacing chooses from
+const GRATICULE_SPACINGS: [f64; 5] = [1.0, 2.0, 5.0, 10.0, 30.0];
+/// The number of grid cells across the viewport used to estimate what is visible
+const VISIBLE_SAMPLES: i32 = 8;
+/// The maximum number of graticule lines that auto_graticule_spacing tries to show
+const MAX_GRATICULE_LINES: f64 = 8.0;
+/// The distance, in pixels, between the points used to estimate pixel_scale_at
+const PIXEL_STEP: f64 = 0.01;
+/// The maximum number of corrections that rotate_globe_by_drag makes to line up the dragged point
+const DRAG_ITERATIONS: usize = 8;
+/// The distance, in pixels, between the dragged point and the pointer that rotate_globe_by_drag
+/// stops correcting at
+const DRAG_TOLERANCE: f64 = 1e-9;
+/// The change in latitude and longitude, in degrees, used to estimate how the dragged point moves
+const DRAG_STEP: f64 = 1e-7;
+/// The smallest zoom that a map can have, which keeps the zoom positive
+const MIN_ZOOM: f64 = 1e-12;
+/// The default vertical field of view, in degrees, used to convert between zoom and altitude
+const DEFAULT_FIELD_OF_VIEW: f64 = 60.0;
+/// The number of intervals between the latitudes that max_distortion_latitude checks
+const DISTORTION_SAMPLES: usize = 64;
+/// The number of intervals between the points along the antimeridian that
+/// viewport_crosses_antimeridian checks
+const ANTIMERIDIAN_SAMPLES: usize = 360;
+/// The number of evenly spaced points on the globe that visible_globe_fraction checks
+const GLOBE_SAMPLES: usize = 4096;
+/// The maximum distance, in pixels, between a picked pixel and the projection of the position
+/// that pick_globe finds for it
+const PICK_TOLERANCE: f64 = 0.5;
 
 ///
 /// Represents a map view
@@ -13,18 +51,23 @@ use layer::Layer;
 ///   and the Y coordinate increases going up.
 /// * Display coordinates: Display coordinates are in pixels, with the X coordinate increasing
 ///   going right and the Y coordinate increasing going up. The origin is located at the
-///   lower left corner of the viewport.
+///   lower left corner of the viewport. Map::set_origin_convention can instead put the origin
+///   at the upper left corner, with the Y coordinate increasing going down.
 ///
 /// The map's projection converts between latitude/longitude and map coordinates. The map's
 /// view projection converts between map coordinates and display coordinates.
 ///
 pub struct Map {
     /// The projection
-    projection: Box<Projection>,
+    projection: Box<dyn Projection>,
     /// The view projection
     view_projection: ViewProjection,
-    /// The layers
-    layers: Vec<Box<Layer>>,
+    /// The layers, in slots that handles refer to
+    layer_slots: Vec<LayerSlot>,
+    /// The indices of the occupied slots in layer_slots, in the order the layers are drawn
+    layer_order: Vec<usize>,
+    /// The indices of the empty slots in layer_slots
+    free_layer_slots: Vec<usize>,
     /// The X location of the lower left corner of the map, in pixels
     x: i32,
     /// The Y location of the lower left corner of the map, in pixels
@@ -33,6 +76,15 @@ pub struct Map {
     width: i32,
     /// The height of the map, in pixels
     height: i32,
+    /// The factor that the zoom changes by for each zoom level
+    zoom_base: f64,
+    /// The vertical angle, in degrees, that the viewport covers when the zoom is described
+    /// as a camera altitude
+    field_of_view: f64,
+    /// The smallest zoom that set_zoom allows
+    min_zoom: f64,
+    /// The largest zoom that set_zoom allows
+    max_zoom: f64,
 }
 
 impl Map {
@@ -45,15 +97,68 @@ impl Map {
             view_projection: ViewProjection {
                 center: Point::origin(),
                 zoom: 1f64,
+                origin: OriginConvention::LowerLeft,
+                offset: Point::origin(),
             },
-            layers: Vec::new(),
-            x: x,
-            y: y,
-            width: width,
-            height: height,
+            layer_slots: Vec::new(),
+            layer_order: Vec::new(),
+            free_layer_slots: Vec::new(),
+            x,
+            y,
+            width,
+            height,
+            zoom_base: 2f64,
+            field_of_view: DEFAULT_FIELD_OF_VIEW,
+            min_zoom: MIN_ZOOM,
+            max_zoom: f64::INFINITY,
+        }
+    }
+
+    ///
+    /// Creates a map using a given projection, showing the view that a camera describes
+    ///
+    /// The lower left corner of the new map is at (0, 0).
+    ///
+    pub fn from_camera<P>(camera: &Camera, projection: P) -> Map where P: 'static + Projection {
+        let mut map = Map::new(projection, 0, 0, camera.width, camera.height);
+        map.set_origin_convention(camera.origin);
+        map.set_view_offset(camera.view_offset.x, camera.view_offset.y);
+        map.set_center(&camera.center);
+        map.set_zoom(camera.zoom);
+        map
+    }
+
+    ///
+    /// Returns a camera that describes the current view of this map
+    ///
+    /// A map created from the camera with Map::from_camera and the same projection shows the
+    /// same view.
+    ///
+    pub fn camera(&self) -> Camera {
+        Camera {
+            center: self.center(),
+            zoom: self.view_projection.zoom,
+            width: self.width,
+            height: self.height,
+            origin: self.view_projection.origin,
+            view_offset: self.view_offset(),
         }
     }
 
+    ///
+    /// Returns the latitude and longitude at the center of the view
+    ///
+    pub fn center(&self) -> LatLon {
+        self.projection.unproject(&self.view_projection.center)
+    }
+
+    ///
+    /// Moves the view so that a latitude and longitude is at its center
+    ///
+    pub fn set_center(&mut self, center: &LatLon) {
+        self.view_projection.center = self.projection.project(center)
+    }
+
     ///
     /// Sets the projection that this map should use
     ///
@@ -64,15 +169,142 @@ impl Map {
     ///
     /// Adds a layer to this map. The new layer will be drawn on top of the existing layers.
     ///
-    pub fn add_layer<L>(&mut self, layer: L) where L: 'static + Layer {
-        self.layers.push(Box::new(layer))
+    /// Returns a handle that can be used to remove the layer later.
+    ///
+    pub fn add_layer<L>(&mut self, layer: L) -> LayerId where L: 'static + Layer {
+        let layer: Box<dyn Layer> = Box::new(layer);
+        let index = match self.free_layer_slots.pop() {
+            Some(index) => {
+                self.layer_slots[index].layer = Some(layer);
+                self.layer_slots[index].visible = true;
+                index
+            }
+            None => {
+                self.layer_slots.push(LayerSlot { generation: 0, layer: Some(layer), visible: true });
+                self.layer_slots.len() - 1
+            }
+        };
+        self.layer_order.push(index);
+        LayerId { index, generation: self.layer_slots[index].generation }
+    }
+
+    ///
+    /// Removes a layer from this map, and returns true if it was removed
+    ///
+    /// Returns false if the layer has already been removed. Handles to the other layers are
+    /// still valid after this.
+    ///
+    pub fn remove_layer(&mut self, id: LayerId) -> bool {
+        if !self.contains_layer(id) {
+            return false;
+        }
+        let slot = &mut self.layer_slots[id.index];
+        slot.layer = None;
+        slot.generation = slot.generation.wrapping_add(1);
+        self.layer_order.retain(|&index| index != id.index);
+        self.free_layer_slots.push(id.index);
+        true
+    }
+
+    ///
+    /// Moves a layer so that it is drawn on top of all other layers, and returns true if the
+    /// layer is on this map
+    ///
+    /// Returns false if the layer has been removed.
+    ///
+    pub fn move_layer_to_top(&mut self, id: LayerId) -> bool {
+        let top = self.layer_order.len();
+        self.move_layer(id, top)
+    }
+
+    ///
+    /// Moves a layer so that it is drawn below all other layers, and returns true if the layer
+    /// is on this map
+    ///
+    /// Returns false if the layer has been removed.
+    ///
+    pub fn move_layer_to_bottom(&mut self, id: LayerId) -> bool {
+        self.move_layer(id, 0)
+    }
+
+    ///
+    /// Moves a layer to a position in the drawing order, and returns true if the layer is on
+    /// this map
+    ///
+    /// Layers are numbered from 0 in the order that they are drawn, and the layer will be
+    /// at new_index after it is moved. An index past the last layer moves the layer to the top.
+    /// A layer that is already at new_index stays there, and this still returns true. Returns
+    /// false if the layer has been removed.
+    ///
+    pub fn move_layer(&mut self, id: LayerId, new_index: usize) -> bool {
+        if !self.contains_layer(id) {
+            return false;
+        }
+        self.layer_order.retain(|&index| index != id.index);
+        let new_index = new_index.min(self.layer_order.len());
+        self.layer_order.insert(new_index, id.index);
+        true
+    }
+
+    ///
+    /// Shows or hides a layer, and returns true if the layer is on this map
+    ///
+    /// Hidden layers stay on the map in the same place in the drawing order, but are not drawn.
+    /// Layers are visible when they are added.
+    ///
+    pub fn set_layer_visible(&mut self, id: LayerId, visible: bool) -> bool {
+        if !self.contains_layer(id) {
+            return false;
+        }
+        self.layer_slots[id.index].visible = visible;
+        true
+    }
+
+    ///
+    /// Returns true if a layer is on this map and is visible
+    ///
+    pub fn is_layer_visible(&self, id: LayerId) -> bool {
+        self.contains_layer(id) && self.layer_slots[id.index].visible
+    }
+
+    ///
+    /// Returns true if a handle refers to a layer that is on this map
+    ///
+    pub fn contains_layer(&self, id: LayerId) -> bool {
+        match self.layer_slots.get(id.index) {
+            Some(slot) => slot.generation == id.generation && slot.layer.is_some(),
+            None => false,
+        }
     }
 
     ///
     /// Removes all layers from this map
     ///
+    /// Handles to the removed layers are no longer valid.
+    ///
     pub fn clear_layers(&mut self) {
-        self.layers.clear()
+        for index in self.layer_order.drain(..) {
+            let slot = &mut self.layer_slots[index];
+            slot.layer = None;
+            slot.generation = slot.generation.wrapping_add(1);
+            self.free_layer_slots.push(index);
+        }
+    }
+
+    ///
+    /// Returns the smallest rectangle that contains the bounds of all layers, or None if no
+    /// layer has bounds
+    ///
+    /// Layers that return None from bounds, because their bounds are not known or they cover
+    /// the whole globe, are ignored.
+    ///
+    pub fn content_bounds(&self) -> Option<LatLonRect> {
+        self.layers()
+            .filter_map(|layer| layer.bounds())
+            .fold(None, |content: Option<LatLonRect>, bounds| match content {
+                Some(content) => Some(content.union(&bounds)),
+                None => Some(bounds),
+            })
     }
 
     ///
@@ -85,8 +317,263 @@ impl Map {
     ///
     /// Sets the map zoom level
     ///
+    /// The zoom is limited to the range from min_zoom to max_zoom. Zooms that are zero or
+    /// negative become min_zoom. Zooms that are NaN or infinite are ignored and the zoom does
+    /// not change.
+    ///
     pub fn set_zoom(&mut self, zoom: f64) {
-        self.view_projection.zoom = zoom
+        if zoom.is_finite() {
+            self.view_projection.zoom = zoom.clamp(self.min_zoom, self.max_zoom)
+        }
+    }
+
+    ///
+    /// Sets the zoom while keeping the same map position at a point in display coordinates
+    ///
+    /// This is for zooming with a scroll wheel, where the position under the pointer should
+    /// stay under the pointer. The zoom is limited in the same way as set_zoom.
+    ///
+    pub fn zoom_around(&mut self, pixel: &Point<f64>, new_zoom: f64) {
+        let before = self.view_projection.unproject(pixel, self.width, self.height);
+        self.set_zoom(new_zoom);
+        let after = self.view_projection.unproject(pixel, self.width, self.height);
+        self.view_projection.center = self.view_projection.center.clone() + (before - after);
+    }
+
+    ///
+    /// Returns the smallest zoom that set_zoom allows
+    ///
+    pub fn min_zoom(&self) -> f64 {
+        self.min_zoom
+    }
+
+    ///
+    /// Sets the smallest zoom that set_zoom allows, and zooms in if the current zoom is smaller
+    ///
+    /// The smallest zoom is always positive, so a zoom that is zero or negative is replaced
+    /// with a very small positive zoom. If the smallest zoom is larger than max_zoom, max_zoom
+    /// is also set to it.
+    ///
+    pub fn set_min_zoom(&mut self, zoom: f64) {
+        self.min_zoom = if zoom > MIN_ZOOM { zoom } else { MIN_ZOOM };
+        self.max_zoom = self.max_zoom.max(self.min_zoom);
+        let current = self.view_projection.zoom;
+        self.set_zoom(current)
+    }
+
+    ///
+    /// Returns the largest zoom that set_zoom allows
+    ///
+    /// By default, there is no limit and this is infinity.
+    ///
+    pub fn max_zoom(&self) -> f64 {
+        self.max_zoom
+    }
+
+    ///
+    /// Sets the largest zoom that set_zoom allows, and zooms out if the current zoom is larger
+    ///
+    /// If the largest zoom is smaller than min_zoom, min_zoom is used instead.
+    ///
+    pub fn set_max_zoom(&mut self, zoom: f64) {
+        self.max_zoom = if zoom > self.min_zoom { zoom } else { self.min_zoom };
+        let current = self.view_projection.zoom;
+        self.set_zoom(current)
+    }
+
+    ///
+    /// Returns the size of a pixel in map coordinates, which is the inverse of the zoom
+    ///
+    pub fn map_units_per_pixel(&self) -> f64 {
+        1.0 / self.view_projection.zoom
+    }
+
+    ///
+    /// Returns the number of pixels per map coordinate unit, which is the same as the zoom
+    ///
+    pub fn pixels_per_map_unit(&self) -> f64 {
+        self.view_projection.zoom
+    }
+
+    ///
+    /// Returns the height, in meters, of a camera above the center of the view that would see
+    /// the same view
+    ///
+    /// The camera is modeled as looking straight down, with the height of the viewport covering
+    /// field_of_view degrees. The ground is treated as flat, with the scale at the center of the
+    /// view, so this is only accurate when the view covers a small area.
+    ///
+    pub fn altitude(&self) -> f64 {
+        let (_, meters_per_pixel) = self.pixel_scale_at(&self.center());
+        let half_angle = (self.field_of_view / 2.0).to_radians();
+        self.height as f64 * meters_per_pixel / (2.0 * half_angle.tan())
+    }
+
+    ///
+    /// Sets the zoom so that the view matches what a camera at an altitude in meters above the
+    /// center of the view would see
+    ///
+    /// This uses the same model as altitude. Altitudes that are zero, negative, NaN, or infinite
+    /// are ignored and the zoom does not change.
+    ///
+    pub fn set_altitude(&mut self, meters: f64) {
+        if meters > 0.0 && meters.is_finite() {
+            let zoom = self.view_projection.zoom * self.altitude() / meters;
+            self.set_zoom(zoom)
+        }
+    }
+
+    ///
+    /// Returns the vertical field of view, in degrees, that altitude and set_altitude use
+    ///
+    /// The default is 60 degrees.
+    ///
+    pub fn field_of_view(&self) -> f64 {
+        self.field_of_view
+    }
+
+    ///
+    /// Sets the vertical field of view, in degrees, that altitude and set_altitude use
+    ///
+    /// The field of view must be greater than 0 and less than 180 degrees. Other values are
+    /// ignored and the field of view does not change.
+    ///
+    pub fn set_field_of_view(&mut self, degrees: f64) {
+        if degrees > 0.0 && degrees < 180.0 {
+            self.field_of_view = degrees
+        }
+    }
+
+    ///
+    /// Returns the current zoom level on a logarithmic scale
+    ///
+    /// The zoom level and the zoom are related by zoom = base^level, so with the default
+    /// base of 2 each level doubles the zoom. Level 0 corresponds to a zoom of 1.
+    ///
+    pub fn zoom_level(&self) -> f64 {
+        self.view_projection.zoom.ln() / self.zoom_base.ln()
+    }
+
+    ///
+    /// Sets the zoom using a level on a logarithmic scale
+    ///
+    pub fn set_zoom_level(&mut self, level: f64) {
+        let zoom = self.zoom_base.powf(level);
+        self.set_zoom(zoom)
+    }
+
+    ///
+    /// Zooms in to the next integer zoom level
+    ///
+    pub fn zoom_in_step(&mut self) {
+        let level = self.snapped_zoom_level();
+        self.set_zoom_level(level.floor() + 1.0)
+    }
+
+    ///
+    /// Zooms out to the previous integer zoom level
+    ///
+    pub fn zoom_out_step(&mut self) {
+        let level = self.snapped_zoom_level();
+        self.set_zoom_level(level.ceil() - 1.0)
+    }
+
+    ///
+    /// Returns the zoom level, rounded to an integer if it is within rounding error of one
+    ///
+    fn snapped_zoom_level(&self) -> f64 {
+        let level = self.zoom_level();
+        let rounded = level.round();
+        if (level - rounded).abs() < 1e-9 {
+            rounded
+        } else {
+            level
+        }
+    }
+
+    ///
+    /// Returns the factor that the zoom changes by for each zoom level
+    ///
+    pub fn zoom_base(&self) -> f64 {
+        self.zoom_base
+    }
+
+    ///
+    /// Sets the factor that the zoom changes by for each zoom level
+    ///
+    /// The base must be finite and greater than 1. Other bases are ignored and the base does not
+    /// change. The default is 2.
+    ///
+    pub fn set_zoom_base(&mut self, base: f64) {
+        if base > 1.0 && base.is_finite() {
+            self.zoom_base = base
+        }
+    }
+
+    ///
+    /// Returns the location of the origin of display coordinates
+    ///
+    pub fn origin_convention(&self) -> OriginConvention {
+        self.view_projection.origin
+    }
+
+    ///
+    /// Sets the location of the origin of display coordinates
+    ///
+    /// This affects the coordinates that layers draw in and all other display coordinates that
+    /// the map accepts or returns.
+    ///
+    pub fn set_origin_convention(&mut self, origin: OriginConvention) {
+        self.view_projection.origin = origin
+    }
+
+    ///
+    /// Returns the offset, in pixels, from the center of the viewport to where the map's center
+    /// is displayed
+    ///
+    pub fn view_offset(&self) -> Point<f64> {
+        self.view_projection.offset.clone()
+    }
+
+    ///
+    /// Sets an offset, in pixels, from the center of the viewport to where the map's center is
+    /// displayed
+    ///
+    /// Unlike scrolling, this does not change the map's center. It moves where the center appears,
+    /// for example to place the map at a fixed position on a printed page.
+    ///
+    pub fn set_view_offset(&mut self, dx: f64, dy: f64) {
+        self.view_projection.offset = Point { x: dx, y: dy }
+    }
+
+    ///
+    /// Returns the continuous tile level that corresponds to the current zoom
+    ///
+    /// Each tile level doubles the zoom, so level 0 corresponds to a zoom of 1.
+    ///
+    pub fn tile_zoom_level(&self) -> f64 {
+        self.view_projection.zoom.log2()
+    }
+
+    ///
+    /// Returns the integer tile level that layers should load at the current zoom
+    ///
+    /// bias, from 0.0 to 1.0, controls how soon the level rounds up to the next level of detail.
+    /// A bias of 0.0 always uses the coarser level, 1.0 switches to the finer level as soon as
+    /// the zoom passes a level boundary, and 0.5 rounds to the nearest level. A bias away from
+    /// 0.5 keeps a zoom that hovers around the middle of a level from switching back and forth.
+    ///
+    /// Zoom levels below 0 are reported as level 0.
+    ///
+    pub fn suggested_tile_level(&self, bias: f64) -> u32 {
+        let bias = bias.clamp(0.0, 1.0);
+        let level = self.tile_zoom_level().max(0.0);
+        let coarse = level.floor();
+        if level - coarse > 1.0 - bias {
+            coarse as u32 + 1
+        } else {
+            coarse as u32
+        }
     }
 
     ///
@@ -103,65 +590,789 @@ impl Map {
         self.height = height;
     }
 
+    ///
+    /// Returns the width and height, in pixels, of an image printed on paper of the provided
+    /// size, in inches, at a resolution in dots per inch
+    ///
+    pub fn print_geometry(&self, paper_width_in: f64, paper_height_in: f64, dpi: f64) -> (i32, i32) {
+        ((paper_width_in * dpi).round() as i32, (paper_height_in * dpi).round() as i32)
+    }
+
+    ///
+    /// Resizes this map to fill paper of the provided size, in inches, at a resolution in dots
+    /// per inch
+    ///
+    /// screen_dpi is the resolution of the display that the map is currently shown on.
+    /// The zoom changes so that features have the same physical size on paper as on that
+    /// display, and the center of the view stays at the same latitude and longitude.
+    ///
+    pub fn set_print_geometry(&mut self, paper_width_in: f64, paper_height_in: f64, dpi: f64, screen_dpi: f64) {
+        let (width, height) = self.print_geometry(paper_width_in, paper_height_in, dpi);
+        let (x, y) = (self.x, self.y);
+        self.set_geometry(x, y, width, height);
+        let zoom = self.zoom() * dpi / screen_dpi;
+        self.set_zoom(zoom);
+    }
+
     ///
     /// Scrolls the map by a specified amount in pixels
     ///
+    /// dx and dy are in display coordinates, so with OriginConvention::UpperLeft a positive dy
+    /// scrolls down the screen instead of up.
+    ///
     pub fn scroll(&mut self, dx: i32, dy: i32) {
-        let map_delta = self.view_projection.unproject(&Point { x: dx as f64, y: dy as f64 }, self.width, self.height);
+        self.offset_center_pixels(dx as f64, dy as f64)
+    }
+
+    ///
+    /// Moves the center of the view by a fractional number of pixels in display coordinates
+    ///
+    pub fn offset_center_pixels(&mut self, dx: f64, dy: f64) {
+        let dy = match self.view_projection.origin {
+            OriginConvention::LowerLeft => dy,
+            OriginConvention::UpperLeft => -dy,
+        };
+        let map_delta = Point { x: dx, y: dy } * (1f64 / self.view_projection.zoom);
         self.view_projection.center = self.view_projection.center.clone() + map_delta;
     }
 
     ///
-    /// Draws this map
+    /// Pans the map so that the point from appears where the point to currently appears
     ///
-    pub fn draw(&self) {
-        let combined = CombinedProjection::new(self.projection.as_ref(), &self.view_projection, self.width, self.height);
-        for layer in self.layers.iter() {
-            layer.draw(&combined, self.x, self.y, self.width, self.height);
+    /// This is the geographic equivalent of dragging the map with the mouse.
+    ///
+    pub fn pan_by_latlon_edge(&mut self, from: &LatLon, to: &LatLon) {
+        let from_map = self.projection.project(from);
+        let to_map = self.projection.project(to);
+        self.view_projection.center = self.view_projection.center.clone() + (from_map - to_map);
+    }
+
+    ///
+    /// Centers and zooms the map so that a rectangle fills the viewport, leaving at least
+    /// padding_pixels pixels between the rectangle and each edge of the viewport
+    ///
+    /// The rectangle is measured between its projected corners. Edges that curve when
+    /// projected may extend past the corners. If the rectangle is a single point, this
+    /// centers the map on it without changing the zoom. A view offset set with set_view_offset
+    /// moves the rectangle away from the center of the viewport by the same amount.
+    ///
+    pub fn fit_to_bounds(&mut self, bounds: &LatLonRect, padding_pixels: i32) {
+        let (min, max) = layer::projected_extent(self.projection.as_ref(), &bounds.corners());
+        let size = max.clone() - min.clone();
+        self.view_projection.center = (min + max) * 0.5;
+        let available = |pixels: i32| f64::max((pixels - 2 * padding_pixels) as f64, 1.0);
+        let zoom = f64::min(available(self.width) / size.x, available(self.height) / size.y);
+        if zoom.is_finite() {
+            self.set_zoom(zoom);
         }
     }
-}
 
-///
-/// A linear projection that maps between map coordinates and display coordinates
-///
-struct ViewProjection {
-    /// The location, in map coordinates, where the center of the viewport is located
-    center: Point<f64>,
-    /// The ratio of the size of a display unit to the size of a map coordinate unit
-    zoom: f64,
-}
+    ///
+    /// Centers and zooms the map so that the bounds of all layers fill the viewport, and returns
+    /// true, or returns false without changing the map if no layer has bounds
+    ///
+    /// This uses content_bounds and fit_to_bounds.
+    ///
+    pub fn fit_content(&mut self, padding_pixels: i32) -> bool {
+        match self.content_bounds() {
+            Some(bounds) => {
+                self.fit_to_bounds(&bounds, padding_pixels);
+                true
+            }
+            None => false,
+        }
+    }
 
-impl ViewProjection {
-    /// Projects a point in map coordinates to a point in screen coordinates
-    pub fn project(&self, map: &Point<f64>, viewport_width: i32, viewport_height: i32) -> Point<f64> {
-        // Calculate the vector from the center point to the map point
-        let mut map_vector = map.clone() - self.center.clone();
-        // Scale by the zoom ratio
-        map_vector = map_vector * self.zoom;
-        // map_vector is now the screen position relative to the center
-        // Shift it to make it relative to the corner
-        map_vector = map_vector + Point { x: (viewport_width / 2) as f64, y: (viewport_height / 2) as f64 };
-        map_vector
+    ///
+    /// Turns the globe so that the position under one point in display coordinates moves to
+    /// another point in display coordinates
+    ///
+    /// This is for dragging the globe with a pointer: from is where the drag started, and to is
+    /// where the pointer is now. For projections with a natural origin, like azimuthal
+    /// projections, this rotates the natural origin so that the grabbed position follows the
+    /// pointer. Projections without a natural origin are panned instead. If either point is not
+    /// on the globe, the map does not change.
+    ///
+    pub fn rotate_globe_by_drag(&mut self, from: &Point<f64>, to: &Point<f64>) {
+        let grabbed = match self.pick_globe(from) {
+            Some(grabbed) => Vector3::from_latlon(&grabbed),
+            None => return,
+        };
+        let origin = match self.projection.natural_origin() {
+            Some(origin) => origin,
+            None => return self.pan_by_drag(from, to),
+        };
+        // Rotating the origin along the great circle from the position under the pointer to the
+        // grabbed position moves the grabbed position close to the pointer
+        let under_pointer = match self.pick_globe(to) {
+            Some(under_pointer) => Vector3::from_latlon(&under_pointer),
+            None => return,
+        };
+        let axis = under_pointer.cross(grabbed);
+        let axis_length = axis.magnitude();
+        if axis_length < 1e-15 {
+            return;
+        }
+        let mut origin = Vector3::from_latlon(&origin).rotate(axis * (1.0 / axis_length), under_pointer.angle_to(grabbed)).to_latlon();
+        if !self.projection.set_natural_origin(origin.clone()) {
+            return self.pan_by_drag(from, to);
+        }
+        let grabbed = grabbed.to_latlon();
+
+        // Because north stays up, that rotation also turns the view a little, so use Newton's
+        // method to remove the remaining error
+        let error = |map: &mut Map, origin: &LatLon| {
+            map.projection.set_natural_origin(origin.clone());
+            map.combined_projection().project(&grabbed) - to.clone()
+        };
+        for _ in 0..DRAG_ITERATIONS {
+            let current = error(self, &origin);
+            if !(current.x.is_finite() && current.y.is_finite()) || f64::hypot(current.x, current.y) < DRAG_TOLERANCE {
+                break;
+            }
+            let north = error(self, &LatLon { latitude: origin.latitude + Latitude(DRAG_STEP), longitude: origin.longitude });
+            let east = error(self, &LatLon { latitude: origin.latitude, longitude: origin.longitude + Longitude(DRAG_STEP) });
+            let d_north = (north - current.clone()) * (1.0 / DRAG_STEP);
+            let d_east = (east - current.clone()) * (1.0 / DRAG_STEP);
+            let determinant = d_north.x * d_east.y - d_east.x * d_north.y;
+            if determinant == 0.0 || !determinant.is_finite() {
+                break;
+            }
+            let step_latitude = (-current.x * d_east.y + current.y * d_east.x) / determinant;
+            let step_longitude = (-d_north.x * current.y + d_north.y * current.x) / determinant;
+            origin = normalize_latlon(&LatLon {
+                latitude: origin.latitude + Latitude(step_latitude),
+                longitude: origin.longitude + Longitude(step_longitude),
+            });
+        }
+        self.projection.set_natural_origin(origin);
     }
-    /// Unprojects a point from screen coordinates to a point in map coordinates
-    pub fn unproject(&self, screen: &Point<f64>, viewport_width: i32, viewport_height: i32) -> Point<f64> {
-        // Shift to make it relative to the center
-        let mut map_vector = screen.clone() - Point { x: (viewport_width / 2) as f64, y: (viewport_height / 2) as f64 };
-        // Scale by inverse zoom ratio
-        map_vector = map_vector * (1f64 / self.zoom);
-        // Make relative to center point
-        map_vector = map_vector + self.center.clone();
-        map_vector
+
+    ///
+    /// Moves the center of the view so that the map point under one point in display
+    /// coordinates moves to another
+    ///
+    fn pan_by_drag(&mut self, from: &Point<f64>, to: &Point<f64>) {
+        let from_map = self.view_projection.unproject(from, self.width, self.height);
+        let to_map = self.view_projection.unproject(to, self.width, self.height);
+        self.view_projection.center = self.view_projection.center.clone() + (from_map - to_map);
     }
-}
 
-///
-/// A Projection implementation for a combination of a Projection and a ViewProjection
-///
-struct CombinedProjection<'a, 'b> {
-    /// The primary projection
-    projection: &'a Projection,
+    ///
+    /// Returns the latitude and longitude spacings, in degrees, for graticule lines at the
+    /// current view
+    ///
+    /// Each spacing is the smallest of 1, 2, 5, 10, and 30 degrees that shows at most 8 lines
+    /// across the viewport. When the map is zoomed out far enough, more than 8 lines may be
+    /// visible at the maximum spacing of 30 degrees.
+    ///
+    pub fn auto_graticule_spacing(&self) -> (f64, f64) {
+        let (latitude_span, longitude_span) = self.visible_spans();
+        (graticule_spacing(latitude_span), graticule_spacing(longitude_span))
+    }
+
+    ///
+    /// Returns the westernmost and easternmost longitudes that are visible along a latitude,
+    /// or None if no part of that latitude is visible
+    ///
+    /// Under non-cylindrical projections, lines of latitude are curved and the visible range
+    /// of longitude depends on the latitude. This samples longitudes along the latitude within
+    /// the approximate visible range of longitude and refines the ends, so the result is
+    /// approximate. If the visible part of the latitude crosses the antimeridian, the result
+    /// spans the whole range that the samples cover.
+    ///
+    pub fn visible_longitude_range_at(&self, latitude: Latitude) -> Option<(Longitude, Longitude)> {
+        const SAMPLES: i32 = 256;
+        const REFINEMENTS: i32 = 32;
+        let (_, (west, east)) = self.visible_extremes()?;
+        // Expand by one grid cell in case the grid missed the edges of the visible area
+        let margin = (east - west) / VISIBLE_SAMPLES as f64;
+        let west = (west - margin).max(-180.0);
+        let east = (east + margin).min(180.0);
+
+        let projection = self.combined_projection();
+        let visible = |longitude: f64| {
+            let ll = LatLon { latitude, longitude: Longitude(longitude) };
+            projection.try_project(&ll).map(|pixel| self.in_viewport(&pixel)).unwrap_or(false)
+        };
+        let step = (east - west) / SAMPLES as f64;
+        let samples: Vec<f64> = (0..(SAMPLES + 1)).map(|i| west + step * i as f64).collect();
+        let first = samples.iter().position(|&longitude| visible(longitude))?;
+        let last = samples.iter().rposition(|&longitude| visible(longitude))?;
+
+        // Narrow down each edge between a visible sample and the invisible sample next to it
+        let refine = |mut inside: f64, mut outside: f64| {
+            for _ in 0..REFINEMENTS {
+                let middle = (inside + outside) / 2.0;
+                if visible(middle) {
+                    inside = middle;
+                } else {
+                    outside = middle;
+                }
+            }
+            inside
+        };
+        let min = if first > 0 { refine(samples[first], samples[first - 1]) } else { samples[first] };
+        let max = if last < samples.len() - 1 { refine(samples[last], samples[last + 1]) } else { samples[last] };
+        Some((Longitude(min), Longitude(max)))
+    }
+
+    ///
+    /// Returns true if any part of the antimeridian, at 180 or -180 degrees of longitude,
+    /// appears in the viewport
+    ///
+    /// This follows each of the two meridians from pole to pole through evenly spaced
+    /// latitudes, and checks whether the straight lines between the projected points cross
+    /// the viewport. A meridian that curves into the viewport between two of those points
+    /// may be missed.
+    ///
+    pub fn viewport_crosses_antimeridian(&self) -> bool {
+        let projection = self.combined_projection();
+        let (width, height) = (self.width as f64, self.height as f64);
+        let corners = [
+            Point { x: 0.0, y: 0.0 },
+            Point { x: width, y: 0.0 },
+            Point { x: width, y: height },
+            Point { x: 0.0, y: height },
+        ];
+        let crosses_viewport = |start: &Point<f64>, end: &Point<f64>| {
+            self.in_viewport(start) || self.in_viewport(end)
+                || (0..4).any(|i| geometry::segments_touch(start, end, &corners[i], &corners[(i + 1) % 4]))
+        };
+        [180.0, -180.0].iter().any(|&longitude| {
+            let pixels: Vec<Point<f64>> = (0..(ANTIMERIDIAN_SAMPLES + 1))
+                .map(|i| LatLon {
+                    latitude: Latitude(-90.0 + 180.0 * i as f64 / ANTIMERIDIAN_SAMPLES as f64),
+                    longitude: Longitude(longitude),
+                })
+                .filter_map(|position| projection.try_project(&position).ok())
+                .filter(|pixel| pixel.x.is_finite() && pixel.y.is_finite())
+                .collect();
+            pixels.windows(2).any(|pair| crosses_viewport(&pair[0], &pair[1]))
+        })
+    }
+
+    ///
+    /// Returns the latitude and longitude shown at a pixel in window coordinates
+    ///
+    /// Window coordinates are display coordinates moved by the position of the map set with
+    /// set_geometry, so a pixel at (x, y) in the window is at (x - map x, y - map y) in display
+    /// coordinates. This uses the same projection that layers draw with.
+    ///
+    pub fn pixel_to_latlon(&self, pixel: &Point<f64>) -> LatLon {
+        self.combined_projection().unproject(&(pixel.clone() - self.window_offset()))
+    }
+
+    ///
+    /// Returns the pixel in window coordinates where a latitude and longitude is shown
+    ///
+    /// This is the inverse of pixel_to_latlon.
+    ///
+    pub fn latlon_to_pixel(&self, coord: &LatLon) -> Point<f64> {
+        self.combined_projection().project(coord) + self.window_offset()
+    }
+
+    ///
+    /// Projects a polygon into display coordinates
+    ///
+    /// These are the same coordinates that layers draw in.
+    ///
+    pub fn project_polygon(&self, polygon: &Polygon<LatLon>) -> Polygon<Point<f64>> {
+        self.combined_projection().project_poly(polygon)
+    }
+
+    ///
+    /// Projects a polygon's points into display coordinates, replacing the contents of out
+    ///
+    /// Reusing the same buffer for many polygons avoids allocating for each one.
+    ///
+    pub fn project_polygon_into(&self, polygon: &Polygon<LatLon>, out: &mut Vec<Point<f64>>) {
+        let projection = self.combined_projection();
+        out.clear();
+        out.extend(polygon.points().iter().map(|ll| projection.project(ll)));
+    }
+
+    ///
+    /// Returns true if a feature with the provided bounds would appear at least min_pixels wide
+    /// or tall at the current view
+    ///
+    pub fn should_draw_feature(&self, bounds: &LatLonRect, min_pixels: f64) -> bool {
+        layer::should_draw_feature(&self.combined_projection(), bounds, min_pixels)
+    }
+
+    ///
+    /// Returns the transformation from map coordinates to display coordinates as a matrix
+    ///
+    /// The matrix is in row-major order and transforms homogeneous column vectors, so
+    /// multiplying it by [x, y, 1] gives the display coordinates of the map coordinates (x, y).
+    /// APIs that expect column-major matrices, like OpenGL, need the transpose.
+    ///
+    pub fn view_matrix(&self) -> [[f64; 3]; 3] {
+        self.view_projection.matrix(self.width, self.height)
+    }
+
+    ///
+    /// Returns the transformation from display coordinates to map coordinates as a matrix
+    ///
+    /// This is the inverse of view_matrix, in the same layout.
+    ///
+    pub fn inverse_view_matrix(&self) -> [[f64; 3]; 3] {
+        self.view_projection.inverse_matrix(self.width, self.height)
+    }
+
+    ///
+    /// Returns the zoom at which the diagonal of a rectangle, after projection, would be
+    /// target_pixels long
+    ///
+    /// The diagonal is measured across the smallest axis-aligned box of the projected corners.
+    /// If the corners all project to the same point, this returns infinity.
+    ///
+    pub fn zoom_for_bounds_pixel_size(&self, bounds: &LatLonRect, target_pixels: f64) -> f64 {
+        let (min, max) = layer::projected_extent(self.projection.as_ref(), &bounds.corners());
+        let size = max - min;
+        target_pixels / f64::hypot(size.x, size.y)
+    }
+
+    ///
+    /// Returns the latitude and longitude shown at a point in display coordinates, or None if
+    /// the point is not on the globe
+    ///
+    /// Projections that do not show the whole earth, like orthographic projections viewed from
+    /// space, leave parts of the viewport empty. Unprojecting a point there can produce an error,
+    /// a non-finite position, or a position that projects somewhere else, such as a point on
+    /// the far side of the globe. This returns None in all of those cases.
+    ///
+    pub fn pick_globe(&self, screen: &Point<f64>) -> Option<LatLon> {
+        let projection = self.combined_projection();
+        let position = projection.try_unproject(screen).ok()?;
+        if !(position.latitude.0.is_finite() && position.longitude.0.is_finite()) {
+            return None;
+        }
+        let reprojected = projection.try_project(&position).ok()?;
+        let error = reprojected - screen.clone();
+        if f64::hypot(error.x, error.y) <= PICK_TOLERANCE {
+            Some(position)
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Returns the approximate fraction of the earth's surface, from 0 to 1, that is visible
+    /// in the viewport
+    ///
+    /// This checks a fixed set of evenly spaced points on the globe. A point counts as visible
+    /// if it projects into the viewport and the position at that pixel is the same point, so
+    /// points that a projection cannot show, or that are hidden behind other points, are not
+    /// counted. For projections that cannot show the whole earth, the result stays below 1 at
+    /// any zoom.
+    ///
+    pub fn visible_globe_fraction(&self) -> f64 {
+        let projection = self.combined_projection();
+        // A Fibonacci lattice spreads the points evenly by area
+        let golden_angle = PI * (3.0 - f64::sqrt(5.0));
+        let visible = (0..GLOBE_SAMPLES)
+            .map(|i| {
+                let z = 1.0 - (2.0 * i as f64 + 1.0) / GLOBE_SAMPLES as f64;
+                let longitude = (golden_angle * i as f64) % (2.0 * PI) - PI;
+                LatLon {
+                    latitude: Latitude(z.asin().to_degrees()),
+                    longitude: Longitude(longitude.to_degrees()),
+                }
+            })
+            .filter(|position| {
+                match projection.try_project(position) {
+                    Ok(ref pixel) if self.in_viewport(pixel) => {
+                        let shown = projection.unproject(pixel);
+                        Vector3::from_latlon(position).angle_to(Vector3::from_latlon(&shown)) < 1e-6
+                    }
+                    _ => false,
+                }
+            })
+            .count();
+        visible as f64 / GLOBE_SAMPLES as f64
+    }
+
+    ///
+    /// Returns a vector of length 1 in display coordinates that points in a compass direction
+    /// at a position
+    ///
+    /// bearing_degrees is measured clockwise from true north. Under most projections,
+    /// the directions on the display that point north or east change from place to place,
+    /// so labels and arrows that need to line up with true directions should use this instead
+    /// of assuming that north is up. If the direction cannot be estimated, both coordinates of
+    /// the result are NaN.
+    ///
+    pub fn screen_vector_for_geographic_direction(&self, at: &LatLon, bearing_degrees: f64) -> Point<f64> {
+        layer::geographic_direction(&self.combined_projection(), at, bearing_degrees)
+    }
+
+    ///
+    /// Returns the approximate distances on the earth's surface, in meters, covered by one pixel
+    /// along the display X and Y axes at a position
+    ///
+    /// Projections that are not conformal stretch distances differently in different
+    /// directions, so the two values can differ. They are estimated from the positions at
+    /// pixels very close to the position's pixel, using the earth's mean radius.
+    ///
+    pub fn pixel_scale_at(&self, point: &LatLon) -> (f64, f64) {
+        let projection = self.combined_projection();
+        let pixel = projection.project(point);
+        let center = Vector3::from_latlon(&projection.unproject(&pixel));
+        let meters_per_pixel = |step: Point<f64>| {
+            let other = Vector3::from_latlon(&projection.unproject(&(pixel.clone() + step)));
+            center.angle_to(other) * EARTH_MEAN_RADIUS / PIXEL_STEP
+        };
+        (meters_per_pixel(Point { x: PIXEL_STEP, y: 0.0 }), meters_per_pixel(Point { x: 0.0, y: PIXEL_STEP }))
+    }
+
+    ///
+    /// Returns the approximate area scale at a position: the number of square pixels that
+    /// one square meter on the earth's surface covers
+    ///
+    /// This is estimated from the positions at pixels very close to the position's pixel,
+    /// using the earth's mean radius. Where the projection cannot be estimated, such as at
+    /// a point that projects to infinity, the result is not finite.
+    ///
+    pub fn distortion_at(&self, point: &LatLon) -> f64 {
+        let projection = self.combined_projection();
+        let pixel = projection.project(point);
+        let center = Vector3::from_latlon(&projection.unproject(&pixel));
+        let edge = |step: Point<f64>| {
+            Vector3::from_latlon(&projection.unproject(&(pixel.clone() + step))) + center * -1.0
+        };
+        let x = edge(Point { x: PIXEL_STEP, y: 0.0 });
+        let y = edge(Point { x: 0.0, y: PIXEL_STEP });
+        let square_meters = x.cross(y).magnitude() * EARTH_MEAN_RADIUS * EARTH_MEAN_RADIUS;
+        PIXEL_STEP * PIXEL_STEP / square_meters
+    }
+
+    ///
+    /// Returns the visible latitude where the area scale, as distortion_at reports it, is largest
+    ///
+    /// This checks evenly spaced latitudes between the southernmost and northernmost visible
+    /// latitudes, along the longitude at the center of the view. If no part of the viewport
+    /// shows the earth, this returns the latitude at the center of the view.
+    ///
+    pub fn max_distortion_latitude(&self) -> Latitude {
+        let center = self.center();
+        let (south, north) = match self.visible_extremes() {
+            Some((latitudes, _)) => latitudes,
+            None => return center.latitude,
+        };
+        (0..(DISTORTION_SAMPLES + 1))
+            .map(|i| Latitude(south + (north - south) * i as f64 / DISTORTION_SAMPLES as f64))
+            .map(|latitude| (latitude, self.distortion_at(&LatLon { latitude, longitude: center.longitude })))
+            .filter(|&(_, scale)| scale.is_finite())
+            .fold(None, |best: Option<(Latitude, f64)>, (latitude, scale)| match best {
+                Some((_, best_scale)) if best_scale >= scale => best,
+                _ => Some((latitude, scale)),
+            })
+            .map(|(latitude, _)| latitude)
+            .unwrap_or(center.latitude)
+    }
+
+    ///
+    /// Returns the approximate radius, in pixels, of a circle on the earth's surface
+    ///
+    /// radius_meters is the radius of the circle along the earth's surface. Projections can
+    /// stretch the circle more in one direction than another, so this is the average of
+    /// half of its projected east-west width and half of its projected north-south height.
+    ///
+    pub fn circle_screen_radius(&self, center: &LatLon, radius_meters: f64) -> f64 {
+        let projection = self.combined_projection();
+        let edge = |bearing: f64| {
+            let position = center.destination(bearing, radius_meters, EARTH_MEAN_RADIUS);
+            // Keep the longitude continuous with the center
+            projection.project(&LatLon {
+                latitude: position.latitude,
+                longitude: center.longitude + normalize_longitude(position.longitude - center.longitude),
+            })
+        };
+        let width = edge(90.0) - edge(270.0);
+        let height = edge(0.0) - edge(180.0);
+        (f64::hypot(width.x, width.y) + f64::hypot(height.x, height.y)) / 4.0
+    }
+
+    ///
+    /// Returns the minimum and maximum display coordinates of the corners of a layer's bounds,
+    /// or None if the layer does not have bounds or has been removed
+    ///
+    /// Hidden layers have screen rectangles too.
+    ///
+    pub fn layer_screen_rect(&self, id: LayerId) -> Option<(Point<f64>, Point<f64>)> {
+        if !self.contains_layer(id) {
+            return None;
+        }
+        let bounds = self.layer_slots[id.index].layer.as_ref()?.bounds()?;
+        Some(layer::projected_extent(&self.combined_projection(), &bounds.corners()))
+    }
+
+    ///
+    /// Draws this map's visible layers onto a canvas, from the bottom layer to the top layer
+    ///
+    pub fn draw(&self, canvas: &mut dyn Canvas) {
+        let combined = self.combined_projection();
+        let visible = self.layer_order.iter()
+            .map(|&index| &self.layer_slots[index])
+            .filter(|slot| slot.visible)
+            .filter_map(|slot| slot.layer.as_ref());
+        for layer in visible {
+            layer.draw(canvas, &combined, self.x, self.y, self.width, self.height);
+        }
+    }
+
+    ///
+    /// Returns the approximate ranges of latitude and longitude, in degrees, that are visible
+    ///
+    fn visible_spans(&self) -> (f64, f64) {
+        match self.visible_extremes() {
+            Some(((south, north), (west, east))) => (north - south, east - west),
+            None => (0.0, 0.0),
+        }
+    }
+
+    ///
+    /// Returns the approximate minimum and maximum latitudes and longitudes that are visible,
+    /// or None if no part of the viewport unprojects to a finite position
+    ///
+    /// This samples a grid of VISIBLE_SAMPLES + 1 by VISIBLE_SAMPLES + 1 points across
+    /// the viewport.
+    ///
+    fn visible_extremes(&self) -> Option<((f64, f64), (f64, f64))> {
+        let projection = self.combined_projection();
+        let mut latitudes = (f64::INFINITY, f64::NEG_INFINITY);
+        let mut longitudes = (f64::INFINITY, f64::NEG_INFINITY);
+        for i in 0..(VISIBLE_SAMPLES + 1) {
+            for j in 0..(VISIBLE_SAMPLES + 1) {
+                let pixel = Point {
+                    x: (self.width * i) as f64 / VISIBLE_SAMPLES as f64,
+                    y: (self.height * j) as f64 / VISIBLE_SAMPLES as f64,
+                };
+                if let Ok(ll) = projection.try_unproject(&pixel) {
+                    let latitude: f64 = ll.latitude.into();
+                    let longitude: f64 = ll.longitude.into();
+                    if latitude.is_finite() && longitude.is_finite() {
+                        latitudes = (latitudes.0.min(latitude), latitudes.1.max(latitude));
+                        longitudes = (longitudes.0.min(longitude), longitudes.1.max(longitude));
+                    }
+                }
+            }
+        }
+        if latitudes.0 <= latitudes.1 {
+            Some((latitudes, longitudes))
+        } else {
+            None
+        }
+    }
+
+    ///
+    /// Returns true if a point in display coordinates is inside the viewport
+    ///
+    fn in_viewport(&self, pixel: &Point<f64>) -> bool {
+        pixel.x >= 0.0 && pixel.x <= self.width as f64 && pixel.y >= 0.0 && pixel.y <= self.height as f64
+    }
+
+    ///
+    /// Returns the layers in the order that they are drawn
+    ///
+    fn layers(&self) -> impl Iterator<Item = &dyn Layer> {
+        self.layer_order.iter()
+            .filter_map(move |&index| self.layer_slots[index].layer.as_ref())
+            .map(|layer| layer.as_ref())
+    }
+
+    ///
+    /// Returns the position of the map in window coordinates
+    ///
+    fn window_offset(&self) -> Point<f64> {
+        Point { x: self.x as f64, y: self.y as f64 }
+    }
+
+    ///
+    /// Returns a projection between latitude/longitude and display coordinates
+    ///
+    fn combined_projection(&self) -> CombinedProjection<'_, '_> {
+        CombinedProjection::new(self.projection.as_ref(), &self.view_projection, self.width, self.height)
+    }
+}
+
+///
+/// A description of a map view that can be used to show the same view on another map
+///
+/// With the serde feature, a camera can be saved, for example as JSON, and restored later.
+///
+#[derive(Debug,Clone,PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Camera {
+    /// The latitude/longitude at the center of the view
+    pub center: LatLon,
+    /// The zoom
+    pub zoom: f64,
+    /// The width of the view, in pixels
+    pub width: i32,
+    /// The height of the view, in pixels
+    pub height: i32,
+    /// The location of the origin of display coordinates
+    pub origin: OriginConvention,
+    /// The offset, in pixels, from the center of the viewport to where the center is displayed
+    pub view_offset: Point<f64>,
+}
+
+///
+/// Returns the smallest graticule spacing that shows at most MAX_GRATICULE_LINES lines across a
+/// span of degrees
+///
+fn graticule_spacing(span: f64) -> f64 {
+    let largest = GRATICULE_SPACINGS[GRATICULE_SPACINGS.len() - 1];
+    GRATICULE_SPACINGS.iter()
+        .cloned()
+        .find(|spacing| span / spacing <= MAX_GRATICULE_LINES)
+        .unwrap_or(largest)
+}
+
+///
+/// Returns the factor that the zoom is multiplied by when changing from one zoom to another
+///
+pub fn zoom_ratio(from: f64, to: f64) -> f64 {
+    to / from
+}
+
+///
+/// Interpolates between two zooms
+///
+/// The interpolation is linear in zoom levels rather than in the zoom itself, so an animation
+/// that advances t at a constant rate appears to zoom at a constant speed. t = 0 returns from,
+/// and t = 1 returns to.
+///
+pub fn interpolate_zoom(from: f64, to: f64, t: f64) -> f64 {
+    from * zoom_ratio(from, to).powf(t)
+}
+
+///
+/// A handle to a layer on a map
+///
+/// Map::add_layer returns a handle. A handle stops referring to its layer when the layer is
+/// removed, even if another layer is later added in its place.
+///
+#[derive(Debug,Copy,Clone,PartialEq,Eq,Hash)]
+pub struct LayerId {
+    /// The index of the layer's slot
+    index: usize,
+    /// The generation of the slot when the layer was added
+    generation: u32,
+}
+
+///
+/// A place for a layer on a map
+///
+struct LayerSlot {
+    /// The number of times a layer has been removed from this slot
+    generation: u32,
+    /// The layer, or None if the slot is empty
+    layer: Option<Box<dyn Layer>>,
+    /// If the layer is drawn
+    visible: bool,
+}
+
+///
+/// The corner of the viewport where display coordinates have their origin
+///
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OriginConvention {
+    /// The origin is at the lower left corner and the Y coordinate increases going up
+    LowerLeft,
+    /// The origin is at the upper left corner and the Y coordinate increases going down,
+    /// as in many 2D drawing APIs
+    UpperLeft,
+}
+
+///
+/// A linear projection that maps between map coordinates and display coordinates
+///
+struct ViewProjection {
+    /// The location, in map coordinates, where the center of the viewport is located
+    center: Point<f64>,
+    /// The ratio of the size of a display unit to the size of a map coordinate unit
+    zoom: f64,
+    /// The corner where display coordinates have their origin
+    origin: OriginConvention,
+    /// The offset, in display coordinates, from the center of the viewport to the location
+    /// where the center point is displayed
+    offset: Point<f64>,
+}
+
+impl ViewProjection {
+    /// Projects a point in map coordinates to a point in screen coordinates
+    pub fn project(&self, map: &Point<f64>, viewport_width: i32, viewport_height: i32) -> Point<f64> {
+        // Calculate the vector from the center point to the map point
+        let mut map_vector = map.clone() - self.center.clone();
+        // Scale by the zoom ratio
+        map_vector = map_vector * self.zoom;
+        // map_vector is now the screen position relative to the center
+        // Shift it to make it relative to the corner
+        map_vector = map_vector + Point { x: (viewport_width / 2) as f64, y: (viewport_height / 2) as f64 };
+        self.flip(map_vector, viewport_height) + self.offset.clone()
+    }
+    /// Unprojects a point from screen coordinates to a point in map coordinates
+    pub fn unproject(&self, screen: &Point<f64>, viewport_width: i32, viewport_height: i32) -> Point<f64> {
+        // Shift to make it relative to the center
+        let mut map_vector = self.flip(screen.clone() - self.offset.clone(), viewport_height) - Point { x: (viewport_width / 2) as f64, y: (viewport_height / 2) as f64 };
+        // Scale by inverse zoom ratio
+        map_vector = map_vector * (1f64 / self.zoom);
+        // Make relative to center point
+        map_vector = map_vector + self.center.clone();
+        map_vector
+    }
+    /// Returns a matrix that does the same transformation as project
+    pub fn matrix(&self, viewport_width: i32, viewport_height: i32) -> [[f64; 3]; 3] {
+        let half_width = (viewport_width / 2) as f64;
+        let half_height = (viewport_height / 2) as f64;
+        let y_row = [0.0, self.zoom, half_height - self.zoom * self.center.y];
+        let y_row = match self.origin {
+            OriginConvention::LowerLeft => y_row,
+            OriginConvention::UpperLeft => [0.0, -y_row[1], viewport_height as f64 - y_row[2]],
+        };
+        [
+            [self.zoom, 0.0, half_width - self.zoom * self.center.x + self.offset.x],
+            [y_row[0], y_row[1], y_row[2] + self.offset.y],
+            [0.0, 0.0, 1.0],
+        ]
+    }
+    /// Returns a matrix that does the same transformation as unproject
+    pub fn inverse_matrix(&self, viewport_width: i32, viewport_height: i32) -> [[f64; 3]; 3] {
+        let half_width = (viewport_width / 2) as f64;
+        let half_height = (viewport_height / 2) as f64;
+        let y_row = [0.0, 1.0 / self.zoom, self.center.y - half_height / self.zoom];
+        let y_row = match self.origin {
+            OriginConvention::LowerLeft => y_row,
+            OriginConvention::UpperLeft => [0.0, -y_row[1], y_row[2] + viewport_height as f64 / self.zoom],
+        };
+        // Subtracting the offset first shifts the translation by the linear part times the offset
+        [
+            [1.0 / self.zoom, 0.0, self.center.x - half_width / self.zoom - self.offset.x / self.zoom],
+            [y_row[0], y_row[1], y_row[2] - y_row[1] * self.offset.y],
+            [0.0, 0.0, 1.0],
+        ]
+    }
+    /// Converts between lower-left-origin coordinates and this projection's display coordinates
+    ///
+    /// This conversion is its own inverse.
+    fn flip(&self, point: Point<f64>, viewport_height: i32) -> Point<f64> {
+        match self.origin {
+            OriginConvention::LowerLeft => point,
+            OriginConvention::UpperLeft => Point { x: point.x, y: viewport_height as f64 - point.y },
+        }
+    }
+}
+
+///
+/// A Projection implementation for a combination of a Projection and a ViewProjection
+///
+struct CombinedProjection<'a, 'b> {
+    /// The primary projection
+    projection: &'a dyn Projection,
     /// The view projection
     view_projection: &'b ViewProjection,
     /// The width of the viewport in pixels
@@ -171,12 +1382,12 @@ struct CombinedProjection<'a, 'b> {
 }
 
 impl<'a, 'b> CombinedProjection<'a, 'b> {
-    pub fn new(projection: &'a Projection, view_projection: &'b ViewProjection, viewport_width: i32, viewport_height: i32) -> CombinedProjection<'a, 'b> {
+    pub fn new(projection: &'a dyn Projection, view_projection: &'b ViewProjection, viewport_width: i32, viewport_height: i32) -> CombinedProjection<'a, 'b> {
         CombinedProjection {
-            projection: projection,
-            view_projection: view_projection,
-            viewport_width: viewport_width,
-            viewport_height: viewport_height,
+            projection,
+            view_projection,
+            viewport_width,
+            viewport_height,
         }
     }
 }
@@ -191,3 +1402,1058 @@ impl<'a, 'b> Projection for CombinedProjection<'a, 'b> {
         self.projection.unproject(&map)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use equirectangular::EquirectangularProjection;
+    use stereographic::StereographicProjection;
+    use mock::{MockCanvas, MockLayer, MockProjection};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use mercator::MercatorProjection;
+    use ::close_enough;
+
+    fn test_map() -> Map {
+        Map::new(EquirectangularProjection, 0, 0, 800, 600)
+    }
+
+    #[test]
+    fn test_zoom_level_zero() {
+        let mut map = test_map();
+        map.set_zoom_level(0.0);
+        assert_eq!(1.0, map.zoom());
+        assert_eq!(0.0, map.zoom_level());
+    }
+    #[test]
+    fn test_zoom_level_three() {
+        let mut map = test_map();
+        map.set_zoom_level(3.0);
+        assert_eq!(8.0, map.zoom());
+        assert_eq!(3.0, map.zoom_level());
+    }
+    #[test]
+    fn test_zoom_level_other_base() {
+        let mut map = test_map();
+        map.set_zoom_base(10.0);
+        map.set_zoom_level(2.0);
+        assert_eq!(100.0, map.zoom());
+    }
+    #[test]
+    fn test_zoom_base_invalid() {
+        let mut map = test_map();
+        for &base in [1.0, 0.5, 0.0, -2.0, f64::NAN, f64::INFINITY].iter() {
+            map.set_zoom_base(base);
+            assert_eq!(2.0, map.zoom_base());
+        }
+        map.set_zoom_level(3.0);
+        assert_eq!(8.0, map.zoom());
+    }
+
+    #[test]
+    fn test_pan_by_latlon_edge() {
+        let projection_point = LatLon { latitude: Latitude(-47.6609), longitude: Longitude(57.7184) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        map.set_zoom(1000.0);
+        let from = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let to = LatLon { latitude: Latitude(47.7), longitude: Longitude(-122.1) };
+
+        let target = map.combined_projection().project(&to);
+        map.pan_by_latlon_edge(&from, &to);
+        let dragged = map.combined_projection().project(&from);
+        assert!(close_enough(target.x, dragged.x));
+        assert!(close_enough(target.y, dragged.y));
+    }
+
+    #[test]
+    fn test_zoom_ratio() {
+        assert_eq!(4.0, zoom_ratio(2.0, 8.0));
+    }
+    #[test]
+    fn test_interpolate_zoom_geometric() {
+        assert!(close_enough(2.0, interpolate_zoom(1.0, 4.0, 0.5)));
+    }
+    #[test]
+    fn test_interpolate_zoom_endpoints() {
+        assert!(close_enough(1.0, interpolate_zoom(1.0, 4.0, 0.0)));
+        assert!(close_enough(4.0, interpolate_zoom(1.0, 4.0, 1.0)));
+    }
+
+    #[test]
+    fn test_auto_graticule_spacing_zoom() {
+        let mut map = test_map();
+        map.set_zoom(800.0 / 40.0);
+        let (lat_close, lon_close) = map.auto_graticule_spacing();
+        map.set_zoom(800.0 / 200.0);
+        let (lat_far, lon_far) = map.auto_graticule_spacing();
+        assert!(lat_far > lat_close);
+        assert!(lon_far > lon_close);
+        for spacing in &[lat_close, lon_close, lat_far, lon_far] {
+            assert!(GRATICULE_SPACINGS.contains(spacing));
+        }
+    }
+    #[test]
+    fn test_auto_graticule_spacing_values() {
+        let mut map = test_map();
+        // 40 degrees of longitude and 30 degrees of latitude visible
+        map.set_zoom(800.0 / 40.0);
+        assert_eq!((5.0, 5.0), map.auto_graticule_spacing());
+        // Zoomed in so that less than a degree is visible
+        map.set_zoom(800.0 / 0.5);
+        assert_eq!((1.0, 1.0), map.auto_graticule_spacing());
+        // The whole world
+        map.set_zoom(800.0 / 360.0);
+        assert_eq!((30.0, 30.0), map.auto_graticule_spacing());
+    }
+
+    #[test]
+    fn test_camera_round_trip() {
+        let projection_point = LatLon { latitude: Latitude(-47.6609), longitude: Longitude(57.7184) };
+        let seattle = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        for &origin in [OriginConvention::LowerLeft, OriginConvention::UpperLeft].iter() {
+            let mut map = Map::new(StereographicProjection::new(projection_point.clone()), 0, 0, 800, 600);
+            map.set_origin_convention(origin);
+            map.set_view_offset(35.0, -20.0);
+            map.set_zoom(1000.0);
+            map.pan_by_latlon_edge(&LatLon { latitude: Latitude(47.0), longitude: Longitude(-122.0) }, &seattle);
+            let camera = map.camera();
+            let seattle_pixel = map.combined_projection().project(&seattle);
+
+            map.set_zoom(10.0);
+            map.scroll(100, 100);
+
+            let restored = Map::from_camera(&camera, StereographicProjection::new(projection_point.clone()));
+            let restored_camera = restored.camera();
+            assert!(close_enough(camera.center.latitude.0, restored_camera.center.latitude.0));
+            assert!(close_enough(camera.center.longitude.0, restored_camera.center.longitude.0));
+            assert_eq!(camera.zoom, restored_camera.zoom);
+            assert_eq!((camera.width, camera.height), (restored_camera.width, restored_camera.height));
+            assert_eq!(origin, restored.origin_convention());
+            assert_eq!(Point { x: 35.0, y: -20.0 }, restored.view_offset());
+            let restored_pixel = restored.combined_projection().project(&seattle);
+            assert!(close_enough(seattle_pixel.x, restored_pixel.x));
+            assert!(close_enough(seattle_pixel.y, restored_pixel.y));
+        }
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_camera() {
+        let mut map = test_map();
+        map.set_origin_convention(OriginConvention::UpperLeft);
+        map.set_view_offset(10.0, -5.0);
+        map.set_center(&LatLon { latitude: Latitude(47.5), longitude: Longitude(-122.25) });
+        map.set_zoom(4.0);
+        let camera = map.camera();
+        let json = ::serde_json::to_string(&camera).unwrap();
+        assert_eq!(r#"{"center":{"latitude":47.5,"longitude":-122.25},"zoom":4.0,"width":800,"height":600,"origin":"UpperLeft","view_offset":{"x":10.0,"y":-5.0}}"#, json);
+        assert_eq!(camera, ::serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_visible_longitude_range_narrows() {
+        // Tangent at 45 N, 0 E with about 20 degrees of longitude across the viewport
+        let projection_point = LatLon { latitude: Latitude(-45.0), longitude: Longitude(180.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        map.set_zoom(2292.0);
+        let (center_west, center_east) = map.visible_longitude_range_at(Latitude(45.0)).unwrap();
+        // This latitude curves out of the top of the viewport on both sides
+        let (edge_west, edge_east) = map.visible_longitude_range_at(Latitude(52.0)).unwrap();
+        assert!(edge_west.0 > center_west.0);
+        assert!(edge_east.0 < center_east.0);
+        assert!(center_west.0 < 0.0 && center_east.0 > 0.0);
+    }
+    #[test]
+    fn test_visible_longitude_range_outside() {
+        let projection_point = LatLon { latitude: Latitude(-45.0), longitude: Longitude(180.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        map.set_zoom(2292.0);
+        assert_eq!(None, map.visible_longitude_range_at(Latitude(70.0)));
+    }
+    #[test]
+    fn test_visible_longitude_range_equirectangular() {
+        let mut map = test_map();
+        map.set_zoom(10.0);
+        let (west, east) = map.visible_longitude_range_at(Latitude(0.0)).unwrap();
+        assert!((west.0 + 40.0).abs() < 1e-6);
+        assert!((east.0 - 40.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_project_polygon_into() {
+        let mut map = test_map();
+        map.set_zoom(10.0);
+        let polygon = Polygon::new(&[
+            LatLon { latitude: Latitude(10.0), longitude: Longitude(10.0) },
+            LatLon { latitude: Latitude(-10.0), longitude: Longitude(10.0) },
+            LatLon { latitude: Latitude(-10.0), longitude: Longitude(-10.0) },
+        ]);
+        let mut buffer = Vec::with_capacity(64);
+        buffer.push(Point::origin());
+        map.project_polygon_into(&polygon, &mut buffer);
+        assert_eq!(64, buffer.capacity());
+        assert_eq!(map.project_polygon(&polygon).points(), &buffer[..]);
+        assert_eq!(Point { x: 500.0, y: 400.0 }, buffer[0]);
+    }
+
+    #[test]
+    fn test_should_draw_feature() {
+        let mut map = test_map();
+        let tiny = LatLonRect::from_bounds(Latitude(47.6610), Latitude(47.6609), Longitude(-122.2815), Longitude(-122.2816));
+        map.set_zoom(1.0);
+        assert!(!map.should_draw_feature(&tiny, 2.0));
+        map.set_zoom(100_000.0);
+        assert!(map.should_draw_feature(&tiny, 2.0));
+    }
+
+    #[test]
+    fn test_offset_center_pixels_accumulates() {
+        let mut offset = test_map();
+        offset.set_zoom(4.0);
+        for _ in 0..10 {
+            offset.offset_center_pixels(0.1, -0.1);
+        }
+        let mut scrolled = test_map();
+        scrolled.set_zoom(4.0);
+        scrolled.scroll(1, -1);
+        assert!(close_enough(scrolled.view_projection.center.x, offset.view_projection.center.x));
+        assert!(close_enough(scrolled.view_projection.center.y, offset.view_projection.center.y));
+    }
+    #[test]
+    fn test_scroll_from_offset_center() {
+        let mut map = test_map();
+        map.set_zoom(2.0);
+        map.scroll(10, 20);
+        map.scroll(10, 20);
+        assert_eq!(Point { x: 10.0, y: 20.0 }, map.view_projection.center);
+    }
+
+    fn transform(matrix: &[[f64; 3]; 3], point: &Point<f64>) -> Point<f64> {
+        Point {
+            x: matrix[0][0] * point.x + matrix[0][1] * point.y + matrix[0][2],
+            y: matrix[1][0] * point.x + matrix[1][1] * point.y + matrix[1][2],
+        }
+    }
+
+    #[test]
+    fn test_view_matrix() {
+        let mut map = test_map();
+        map.set_zoom(3.5);
+        map.scroll(17, -40);
+        let point = Point { x: -12.0, y: 33.0 };
+        let expected = map.view_projection.project(&point, map.width, map.height);
+        let transformed = transform(&map.view_matrix(), &point);
+        assert!(close_enough(expected.x, transformed.x));
+        assert!(close_enough(expected.y, transformed.y));
+        assert_eq!([0.0, 0.0, 1.0], map.view_matrix()[2]);
+    }
+    #[test]
+    fn test_inverse_view_matrix() {
+        let mut map = test_map();
+        map.set_zoom(3.5);
+        map.scroll(17, -40);
+        let pixel = Point { x: 120.0, y: 450.0 };
+        let expected = map.view_projection.unproject(&pixel, map.width, map.height);
+        let transformed = transform(&map.inverse_view_matrix(), &pixel);
+        assert!(close_enough(expected.x, transformed.x));
+        assert!(close_enough(expected.y, transformed.y));
+        let round_trip = transform(&map.view_matrix(), &transformed);
+        assert!(close_enough(pixel.x, round_trip.x));
+        assert!(close_enough(pixel.y, round_trip.y));
+    }
+
+    #[test]
+    fn test_upper_left_origin_flips_y() {
+        let mut map = test_map();
+        map.set_zoom(2.0);
+        let ll = LatLon { latitude: Latitude(30.0), longitude: Longitude(40.0) };
+        let lower_left = map.combined_projection().project(&ll);
+        map.set_origin_convention(OriginConvention::UpperLeft);
+        let upper_left = map.combined_projection().project(&ll);
+        assert_eq!(lower_left.x, upper_left.x);
+        assert!(close_enough(600.0 - lower_left.y, upper_left.y));
+        let round_trip = map.combined_projection().unproject(&upper_left);
+        assert!(close_enough(30.0, round_trip.latitude.0));
+        assert!(close_enough(40.0, round_trip.longitude.0));
+    }
+    #[test]
+    fn test_upper_left_origin_matrices() {
+        let mut map = test_map();
+        map.set_zoom(3.5);
+        map.scroll(17, -40);
+        map.set_origin_convention(OriginConvention::UpperLeft);
+        let point = Point { x: -12.0, y: 33.0 };
+        let expected = map.view_projection.project(&point, map.width, map.height);
+        let transformed = transform(&map.view_matrix(), &point);
+        assert!(close_enough(expected.x, transformed.x));
+        assert!(close_enough(expected.y, transformed.y));
+        let back = transform(&map.inverse_view_matrix(), &transformed);
+        assert!(close_enough(point.x, back.x));
+        assert!(close_enough(point.y, back.y));
+    }
+    #[test]
+    fn test_upper_left_scroll_direction() {
+        // Scrolling moves the center toward larger display Y, which is down on the screen when
+        // the origin is at the upper left
+        let mut map = test_map();
+        map.set_origin_convention(OriginConvention::UpperLeft);
+        let ll = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        let before = map.combined_projection().project(&ll);
+        map.scroll(0, 10);
+        let after = map.combined_projection().project(&ll);
+        assert!(close_enough(before.y - 10.0, after.y));
+    }
+
+    #[test]
+    fn test_zoom_in_step() {
+        let mut map = test_map();
+        map.set_zoom_level(0.0);
+        for expected in 1..4 {
+            map.zoom_in_step();
+            assert_eq!(expected as f64, map.zoom_level());
+        }
+    }
+    #[test]
+    fn test_zoom_steps_snap() {
+        let mut map = test_map();
+        map.set_zoom_base(3.0);
+        map.set_zoom_level(2.5);
+        map.zoom_in_step();
+        assert!(close_enough(3.0, map.zoom_level()));
+        map.set_zoom_level(2.5);
+        map.zoom_out_step();
+        assert!(close_enough(2.0, map.zoom_level()));
+        map.zoom_out_step();
+        assert!(close_enough(1.0, map.zoom_level()));
+    }
+
+    #[test]
+    fn test_view_offset() {
+        let projection_point = LatLon { latitude: Latitude(-45.0), longitude: Longitude(180.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        map.set_zoom(1000.0);
+        map.scroll(25, 40);
+        let center = map.camera().center;
+        map.set_view_offset(30.0, -12.5);
+        assert_eq!(center, map.camera().center);
+        let pixel = map.combined_projection().project(&center);
+        assert!(close_enough(430.0, pixel.x));
+        assert!(close_enough(287.5, pixel.y));
+        let unprojected = map.combined_projection().unproject(&pixel);
+        assert!(close_enough(center.latitude.0, unprojected.latitude.0));
+        assert!(close_enough(center.longitude.0, unprojected.longitude.0));
+    }
+    #[test]
+    fn test_view_offset_matrices() {
+        for origin in &[OriginConvention::LowerLeft, OriginConvention::UpperLeft] {
+            let mut map = test_map();
+            map.set_zoom(3.5);
+            map.scroll(17, -40);
+            map.set_origin_convention(*origin);
+            map.set_view_offset(-8.0, 21.0);
+            let point = Point { x: -12.0, y: 33.0 };
+            let expected = map.view_projection.project(&point, map.width, map.height);
+            let transformed = transform(&map.view_matrix(), &point);
+            assert!(close_enough(expected.x, transformed.x));
+            assert!(close_enough(expected.y, transformed.y));
+            let back = transform(&map.inverse_view_matrix(), &transformed);
+            assert!(close_enough(point.x, back.x));
+            assert!(close_enough(point.y, back.y));
+        }
+    }
+
+    #[test]
+    fn test_zoom_for_bounds_pixel_size() {
+        let projection_point = LatLon { latitude: Latitude(-45.0), longitude: Longitude(180.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        let bounds = LatLonRect::from_bounds(Latitude(47.0), Latitude(44.0), Longitude(3.0), Longitude(-1.0));
+        let zoom = map.zoom_for_bounds_pixel_size(&bounds, 250.0);
+        map.set_zoom(zoom);
+        let (min, max) = layer::projected_extent(&map.combined_projection(), &bounds.corners());
+        let size = max - min;
+        assert!(close_enough(250.0, f64::hypot(size.x, size.y)));
+    }
+
+    #[test]
+    fn test_pick_globe() {
+        let mut globe = MockProjection::new();
+        globe.set_fail_outside(Some(LatLonRect::from_bounds(Latitude(60.0), Latitude(-60.0), Longitude(60.0), Longitude(-60.0))));
+        let mut map = Map::new(globe, 0, 0, 800, 600);
+        map.set_zoom(2.0);
+        let center = map.pick_globe(&Point { x: 400.0, y: 300.0 }).unwrap();
+        assert!(close_enough(0.0, center.latitude.0));
+        assert!(close_enough(0.0, center.longitude.0));
+        let edge = map.pick_globe(&Point { x: 500.0, y: 400.0 }).unwrap();
+        assert!(close_enough(50.0, edge.latitude.0));
+        assert!(close_enough(50.0, edge.longitude.0));
+        assert_eq!(None, map.pick_globe(&Point { x: 0.0, y: 0.0 }));
+    }
+    #[test]
+    fn test_pick_globe_far_side() {
+        // This projection folds the map over at x = 100, so points farther right unproject to
+        // positions that project back to the left of the fold
+        struct Folded;
+        impl Projection for Folded {
+            fn project(&self, position: &LatLon) -> Point<f64> {
+                Point { x: position.longitude.0, y: position.latitude.0 }
+            }
+            fn unproject(&self, position: &Point<f64>) -> LatLon {
+                let x = if position.x > 100.0 { 200.0 - position.x } else { position.x };
+                LatLon { latitude: Latitude(position.y), longitude: Longitude(x) }
+            }
+        }
+        let map = Map::new(Folded, 0, 0, 800, 600);
+        assert!(map.pick_globe(&Point { x: 450.0, y: 300.0 }).is_some());
+        assert_eq!(None, map.pick_globe(&Point { x: 550.0, y: 300.0 }));
+    }
+    #[test]
+    fn test_visible_globe_fraction_equirectangular() {
+        let mut map = test_map();
+        let mut last = 0.0;
+        for &zoom in [20.0, 10.0, 5.0, 3.0].iter() {
+            map.set_zoom(zoom);
+            let fraction = map.visible_globe_fraction();
+            assert!(fraction > last);
+            last = fraction;
+        }
+        // 800 by 600 pixels at zoom 2 covers the whole map
+        map.set_zoom(2.0);
+        assert_eq!(1.0, map.visible_globe_fraction());
+    }
+    #[test]
+    fn test_visible_globe_fraction_partial_projection() {
+        // This projection can only show latitudes between 30 south and 30 north,
+        // which is half of the earth's surface
+        let mut band = MockProjection::new();
+        band.set_fail_outside(Some(LatLonRect::from_bounds(Latitude(30.0), Latitude(-30.0), Longitude(180.0), Longitude(-180.0))));
+        let mut map = Map::new(band, 0, 0, 800, 600);
+        let mut last = 0.0;
+        for &zoom in [20.0, 10.0, 5.0, 2.0, 1.0].iter() {
+            map.set_zoom(zoom);
+            let fraction = map.visible_globe_fraction();
+            assert!(fraction >= last);
+            last = fraction;
+        }
+        assert!((last - 0.5).abs() < 0.01);
+    }
+    #[test]
+    fn test_screen_vector_equator() {
+        let mut map = test_map();
+        let at = LatLon { latitude: Latitude(0.0), longitude: Longitude(25.0) };
+        let east = map.screen_vector_for_geographic_direction(&at, 90.0);
+        assert!(close_enough(1.0, east.x));
+        assert!(close_enough(0.0, east.y));
+        let north = map.screen_vector_for_geographic_direction(&at, 0.0);
+        assert!(close_enough(0.0, north.x));
+        assert!(close_enough(1.0, north.y));
+        map.set_origin_convention(OriginConvention::UpperLeft);
+        let north = map.screen_vector_for_geographic_direction(&at, 0.0);
+        assert!(close_enough(-1.0, north.y));
+    }
+    #[test]
+    fn test_screen_vector_convergence() {
+        // Viewed from above the north pole, north points toward the pole from everywhere
+        let projection_point = LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        map.set_zoom(100.0);
+        let at = LatLon { latitude: Latitude(60.0), longitude: Longitude(90.0) };
+        let north = map.screen_vector_for_geographic_direction(&at, 0.0);
+        let to_pole = map.combined_projection().project(&LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) })
+            - map.combined_projection().project(&at);
+        let length = f64::hypot(to_pole.x, to_pole.y);
+        assert!(close_enough(to_pole.x / length, north.x));
+        assert!(close_enough(to_pole.y / length, north.y));
+    }
+    #[test]
+    fn test_pixel_scale_at_equirectangular() {
+        let map = test_map();
+        let meters_per_degree = EARTH_MEAN_RADIUS * PI / 180.0;
+        let (x, y) = map.pixel_scale_at(&LatLon { latitude: Latitude(60.0), longitude: Longitude(10.0) });
+        assert!((x / meters_per_degree - 0.5).abs() < 1e-6);
+        assert!((y / meters_per_degree - 1.0).abs() < 1e-6);
+    }
+    #[test]
+    fn test_pixel_scale_at_stereographic() {
+        // The stereographic projection is conformal, and stretches distances more farther from
+        // the point where the map touches the globe
+        let projection_point = LatLon { latitude: Latitude(0.0), longitude: Longitude(180.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point), 0, 0, 800, 600);
+        map.set_zoom(1000.0);
+        let mut last = f64::INFINITY;
+        for &latitude in [0.0, 20.0, 40.0, 60.0, 80.0].iter() {
+            let (x, y) = map.pixel_scale_at(&LatLon { latitude: Latitude(latitude), longitude: Longitude(0.0) });
+            assert!((x / y - 1.0).abs() < 1e-4);
+            assert!(x < last);
+            last = x;
+        }
+    }
+    #[test]
+    fn test_pixel_scale_at_mercator() {
+        // The Mercator projection is conformal, and one pixel covers less distance farther
+        // from the equator
+        let mut map = Map::new(MercatorProjection::default(), 0, 0, 800, 600);
+        map.set_zoom(0.001);
+        let mut last = f64::INFINITY;
+        for &latitude in [0.0, 20.0, 40.0, 60.0, 80.0].iter() {
+            let (x, y) = map.pixel_scale_at(&LatLon { latitude: Latitude(latitude), longitude: Longitude(0.0) });
+            assert!((x / y - 1.0).abs() < 1e-4);
+            assert!((x - 1000.0 * latitude.to_radians().cos()).abs() < 0.01);
+            assert!(x < last);
+            last = x;
+        }
+    }
+    #[test]
+    fn test_print_geometry() {
+        let map = test_map();
+        // A4 paper is 8.27 by 11.69 inches
+        assert_eq!((2481, 3507), map.print_geometry(8.27, 11.69, 300.0));
+        assert_eq!((800, 600), map.print_geometry(8.0, 6.0, 100.0));
+    }
+    #[test]
+    fn test_set_print_geometry() {
+        let mut map = test_map();
+        map.set_zoom(4.0);
+        map.pan_by_latlon_edge(&LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) },
+            &LatLon { latitude: Latitude(-20.0), longitude: Longitude(30.0) });
+        let center = map.camera().center;
+        map.set_print_geometry(11.0, 8.5, 300.0, 100.0);
+        assert_eq!((3300, 2550), (map.camera().width, map.camera().height));
+        assert_eq!(center, map.camera().center);
+        // A feature is three times as many pixels wide, at three times as many pixels per inch
+        assert!(close_enough(12.0, map.zoom()));
+    }
+    #[test]
+    fn test_rotate_globe_by_drag() {
+        let projection_point = LatLon { latitude: Latitude(-30.0), longitude: Longitude(160.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point.clone()), 0, 0, 800, 600);
+        map.set_zoom(200.0);
+        let from = Point { x: 450.0, y: 320.0 };
+        let to = Point { x: 300.0, y: 200.0 };
+        let grabbed = map.pick_globe(&from).unwrap();
+        map.rotate_globe_by_drag(&from, &to);
+        let released = map.combined_projection().project(&grabbed);
+        assert!(f64::hypot(released.x - to.x, released.y - to.y) < 1e-6);
+        // The projection point moved, instead of the view
+        assert!(map.projection.natural_origin().unwrap() != projection_point);
+    }
+    #[test]
+    fn test_rotate_globe_by_drag_cylindrical() {
+        let mut map = test_map();
+        let from = Point { x: 450.0, y: 320.0 };
+        let to = Point { x: 300.0, y: 200.0 };
+        let grabbed = map.pick_globe(&from).unwrap();
+        map.rotate_globe_by_drag(&from, &to);
+        let released = map.combined_projection().project(&grabbed);
+        assert!(close_enough(to.x, released.x));
+        assert!(close_enough(to.y, released.y));
+    }
+    #[test]
+    fn test_map_units_per_pixel() {
+        let mut map = test_map();
+        for &zoom in [0.25, 1.0, 3.0, 1000.0].iter() {
+            map.set_zoom(zoom);
+            assert_eq!(zoom, map.pixels_per_map_unit());
+            assert!(close_enough(1.0 / zoom, map.map_units_per_pixel()));
+            assert!(close_enough(1.0, map.map_units_per_pixel() * map.pixels_per_map_unit()));
+        }
+    }
+    #[test]
+    fn test_circle_screen_radius_zoom() {
+        let mut map = test_map();
+        let center = LatLon { latitude: Latitude(37.4), longitude: Longitude(-122.1) };
+        let radius = map.circle_screen_radius(&center, 10_000.0);
+        assert!(radius > 0.0);
+        map.set_zoom(map.zoom() * 2.0);
+        assert!((map.circle_screen_radius(&center, 10_000.0) / radius - 2.0).abs() < 1e-9);
+    }
+    #[test]
+    fn test_circle_screen_radius_equirectangular() {
+        // One degree of latitude is about 111 km, and is one map unit tall
+        let map = test_map();
+        let center = LatLon { latitude: Latitude(0.0), longitude: Longitude(179.9) };
+        let meters_per_degree = EARTH_MEAN_RADIUS * PI / 180.0;
+        let radius = map.circle_screen_radius(&center, meters_per_degree);
+        assert!((radius - map.zoom()).abs() < 1e-6 * map.zoom());
+    }
+    #[test]
+    fn test_distortion_at_mercator() {
+        // The Mercator projection stretches areas by the square of the secant of the latitude
+        let mut map = Map::new(MercatorProjection::default(), 0, 0, 800, 600);
+        map.set_zoom(0.001);
+        let equator = map.distortion_at(&LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) });
+        let north = map.distortion_at(&LatLon { latitude: Latitude(60.0), longitude: Longitude(0.0) });
+        assert!((equator - 1e-6).abs() < 1e-10);
+        assert!((north / equator - 4.0).abs() < 1e-3);
+    }
+    #[test]
+    fn test_max_distortion_latitude_mercator() {
+        let camera = Camera {
+            center: LatLon { latitude: Latitude(40.0), longitude: Longitude(10.0) },
+            zoom: 0.0001,
+            width: 800,
+            height: 600,
+            origin: OriginConvention::LowerLeft,
+            view_offset: Point::origin(),
+        };
+        let map = Map::from_camera(&camera, MercatorProjection::default());
+        let top = map.combined_projection().unproject(&Point { x: 400.0, y: 600.0 });
+        let latitude = map.max_distortion_latitude();
+        assert!(latitude.0 > 55.0);
+        assert!((latitude.0 - top.latitude.0).abs() < 0.5);
+    }
+    #[test]
+    fn test_max_distortion_latitude_southern() {
+        let camera = Camera {
+            center: LatLon { latitude: Latitude(-40.0), longitude: Longitude(10.0) },
+            zoom: 0.0001,
+            width: 800,
+            height: 600,
+            origin: OriginConvention::LowerLeft,
+            view_offset: Point::origin(),
+        };
+        let map = Map::from_camera(&camera, MercatorProjection::default());
+        let bottom = map.combined_projection().unproject(&Point { x: 400.0, y: 0.0 });
+        assert!((map.max_distortion_latitude().0 - bottom.latitude.0).abs() < 0.5);
+    }
+    #[test]
+    fn test_layer_screen_rect() {
+        let mut map = test_map();
+        map.set_zoom(2.0);
+        let bounds = LatLonRect::from_bounds(Latitude(40.0), Latitude(30.0), Longitude(-110.0), Longitude(-120.0));
+        let unbounded = map.add_layer(MockLayer::new(None));
+        let bounded = map.add_layer(MockLayer::new(Some(bounds.clone())));
+        let projection = map.combined_projection();
+        let northeast = projection.project(&LatLon { latitude: bounds.north(), longitude: bounds.east() });
+        let southwest = projection.project(&LatLon { latitude: bounds.south(), longitude: bounds.west() });
+        assert_eq!(Some((southwest.clone(), northeast.clone())), map.layer_screen_rect(bounded));
+        assert_eq!(None, map.layer_screen_rect(unbounded));
+        // The handle still refers to the layer after it is moved and hidden, and after other layers are removed
+        assert!(map.move_layer_to_bottom(bounded));
+        assert!(map.set_layer_visible(bounded, false));
+        assert!(map.remove_layer(unbounded));
+        assert_eq!(Some((southwest, northeast)), map.layer_screen_rect(bounded));
+        assert!(map.remove_layer(bounded));
+        assert_eq!(None, map.layer_screen_rect(bounded));
+    }
+    #[test]
+    fn test_altitude_equirectangular() {
+        // At the equator, 600 pixels at a zoom of 1 cover 600 degrees of latitude, and
+        // a 60 degree field of view sees a height of 2 tan(30 degrees) times the altitude
+        let map = test_map();
+        let meters = 600.0 * EARTH_MEAN_RADIUS * PI / 180.0;
+        assert!((map.altitude() / (meters / (2.0 * 30f64.to_radians().tan())) - 1.0).abs() < 1e-6);
+    }
+    #[test]
+    fn test_set_altitude_doubling() {
+        let mut map = test_map();
+        map.set_altitude(10_000.0);
+        assert!((map.altitude() - 10_000.0).abs() < 1e-3);
+        let scale = map.pixels_per_map_unit();
+        map.set_altitude(20_000.0);
+        assert!((map.pixels_per_map_unit() / scale - 0.5).abs() < 1e-6);
+    }
+    #[test]
+    fn test_field_of_view() {
+        let mut map = test_map();
+        assert_eq!(60.0, map.field_of_view());
+        let altitude = map.altitude();
+        // A narrower field of view needs a higher camera to see the same view
+        map.set_field_of_view(30.0);
+        assert!(map.altitude() > altitude);
+    }
+    #[test]
+    fn test_set_altitude_invalid() {
+        let mut map = test_map();
+        map.set_zoom(4.0);
+        for &meters in [0.0, -100.0, f64::NAN, f64::INFINITY].iter() {
+            map.set_altitude(meters);
+            assert_eq!(4.0, map.zoom());
+        }
+    }
+    #[test]
+    fn test_set_field_of_view_invalid() {
+        let mut map = test_map();
+        map.set_field_of_view(45.0);
+        for &degrees in [0.0, -10.0, 180.0, 270.0, f64::NAN].iter() {
+            map.set_field_of_view(degrees);
+            assert_eq!(45.0, map.field_of_view());
+        }
+        assert!(map.altitude().is_finite() && map.altitude() > 0.0);
+    }
+    #[test]
+    fn test_fit_to_bounds() {
+        let mut map = test_map();
+        let bounds = LatLonRect::from_bounds(Latitude(47.7), Latitude(47.5), Longitude(-122.2), Longitude(-122.5));
+        map.fit_to_bounds(&bounds, 20);
+        let projection = map.combined_projection();
+        for corner in bounds.corners().iter() {
+            let pixel = projection.project(corner);
+            assert!(pixel.x >= 20.0 - 1e-6 && pixel.x <= 780.0 + 1e-6);
+            assert!(pixel.y >= 20.0 - 1e-6 && pixel.y <= 580.0 + 1e-6);
+        }
+        // The wider side of the rectangle touches the padding
+        let west = projection.project(&LatLon { latitude: Latitude(47.6), longitude: Longitude(-122.5) });
+        assert!((west.x - 20.0).abs() < 1e-6);
+    }
+    #[test]
+    fn test_fit_to_bounds_stereographic() {
+        let projection_point = LatLon { latitude: Latitude(40.0), longitude: Longitude(-100.0) };
+        let mut map = Map::new(StereographicProjection::new(projection_point.antipode()), 0, 0, 800, 600);
+        let bounds = LatLonRect::from_bounds(Latitude(50.0), Latitude(30.0), Longitude(-80.0), Longitude(-120.0));
+        map.fit_to_bounds(&bounds, 0);
+        let projection = map.combined_projection();
+        for corner in bounds.corners().iter() {
+            let pixel = projection.project(corner);
+            assert!(map.in_viewport(&Point { x: pixel.x.round(), y: pixel.y.round() }));
+        }
+    }
+    #[test]
+    fn test_fit_to_bounds_point() {
+        let mut map = test_map();
+        let point = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+        map.fit_to_bounds(&LatLonRect::from_corners(&point, &point), 10);
+        assert_eq!(1.0, map.zoom());
+        assert_eq!(point, map.camera().center);
+    }
+    #[test]
+    fn test_set_zoom_above_max() {
+        let mut map = test_map();
+        map.set_max_zoom(16.0);
+        map.set_zoom(100.0);
+        assert_eq!(16.0, map.zoom());
+        map.set_zoom(8.0);
+        assert_eq!(8.0, map.zoom());
+    }
+    #[test]
+    fn test_set_zoom_zero() {
+        let mut map = test_map();
+        for &zoom in [0.0, -3.0].iter() {
+            map.set_zoom(zoom);
+            assert!(map.zoom() > 0.0);
+            assert_eq!(map.min_zoom(), map.zoom());
+        }
+        map.set_min_zoom(0.5);
+        map.set_zoom(0.0);
+        assert_eq!(0.5, map.zoom());
+    }
+    #[test]
+    fn test_set_zoom_not_finite() {
+        let mut map = test_map();
+        map.set_zoom(4.0);
+        for &zoom in [f64::INFINITY, f64::NEG_INFINITY, f64::NAN].iter() {
+            map.set_zoom(zoom);
+            assert_eq!(4.0, map.zoom());
+        }
+    }
+    #[test]
+    fn test_zoom_limits_change_current_zoom() {
+        let mut map = test_map();
+        map.set_zoom(4.0);
+        map.set_max_zoom(2.0);
+        assert_eq!(2.0, map.zoom());
+        map.set_min_zoom(3.0);
+        assert_eq!(3.0, map.zoom());
+        assert_eq!(3.0, map.max_zoom());
+        map.set_min_zoom(-1.0);
+        assert!(map.min_zoom() > 0.0);
+    }
+    #[test]
+    fn test_zoom_steps_clamped() {
+        let mut map = test_map();
+        map.set_min_zoom(0.5);
+        map.set_max_zoom(8.0);
+        map.set_zoom(8.0);
+        map.zoom_in_step();
+        assert_eq!(8.0, map.zoom());
+        map.set_zoom(0.5);
+        map.zoom_out_step();
+        assert_eq!(0.5, map.zoom());
+        map.zoom_in_step();
+        assert_eq!(1.0, map.zoom());
+    }
+    fn camera_at(longitude: f64, zoom: f64) -> Camera {
+        Camera {
+            center: LatLon { latitude: Latitude(20.0), longitude: Longitude(longitude) },
+            zoom,
+            width: 800,
+            height: 600,
+            origin: OriginConvention::LowerLeft,
+            view_offset: Point::origin(),
+        }
+    }
+    #[test]
+    fn test_viewport_crosses_antimeridian_near() {
+        assert!(Map::from_camera(&camera_at(170.0, 10.0), EquirectangularProjection).viewport_crosses_antimeridian());
+        assert!(Map::from_camera(&camera_at(-175.0, 10.0), EquirectangularProjection).viewport_crosses_antimeridian());
+        assert!(Map::from_camera(&camera_at(179.9, 1e-3), MercatorProjection::default()).viewport_crosses_antimeridian());
+    }
+    #[test]
+    fn test_viewport_crosses_antimeridian_far() {
+        assert!(!Map::from_camera(&camera_at(0.0, 10.0), EquirectangularProjection).viewport_crosses_antimeridian());
+        assert!(!Map::from_camera(&camera_at(135.0, 10.0), EquirectangularProjection).viewport_crosses_antimeridian());
+        // Zoomed in close to the antimeridian
+        assert!(!Map::from_camera(&camera_at(179.9, 1.0), MercatorProjection::default()).viewport_crosses_antimeridian());
+    }
+    #[test]
+    fn test_viewport_crosses_antimeridian_polar() {
+        // Viewed from above the north pole, the antimeridian leads away from the center of the view
+        let south_pole = LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) };
+        let mut map = Map::new(StereographicProjection::new(south_pole), 0, 0, 800, 600);
+        map.set_zoom(100.0);
+        assert!(map.viewport_crosses_antimeridian());
+    }
+    #[test]
+    fn test_center_round_trip() {
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let mut map = test_map();
+        map.set_center(&center);
+        assert_eq!(center, map.center());
+        let mut mercator = Map::new(MercatorProjection::default(), 0, 0, 800, 600);
+        mercator.set_center(&center);
+        assert!(close_enough(center.latitude.0, mercator.center().latitude.0));
+        assert!(close_enough(center.longitude.0, mercator.center().longitude.0));
+    }
+    #[test]
+    fn test_set_center_moves_view() {
+        let mut map = test_map();
+        map.set_zoom(2.0);
+        let center = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+        map.set_center(&center);
+        assert_eq!(Point { x: 400.0, y: 300.0 }, map.combined_projection().project(&center));
+    }
+    #[test]
+    fn test_content_bounds() {
+        let mut map = test_map();
+        let seattle = LatLonRect::from_bounds(Latitude(47.7), Latitude(47.5), Longitude(-122.2), Longitude(-122.5));
+        let portland = LatLonRect::from_bounds(Latitude(45.6), Latitude(45.4), Longitude(-122.5), Longitude(-122.8));
+        map.add_layer(MockLayer::new(Some(seattle.clone())));
+        map.add_layer(MockLayer::new(None));
+        map.add_layer(MockLayer::new(Some(portland.clone())));
+        let content = map.content_bounds().unwrap();
+        assert!(content.contains_rect(&seattle));
+        assert!(content.contains_rect(&portland));
+        assert_eq!(LatLonRect::from_bounds(Latitude(47.7), Latitude(45.4), Longitude(-122.2), Longitude(-122.8)), content);
+    }
+    #[test]
+    fn test_content_bounds_unbounded() {
+        let mut map = test_map();
+        assert_eq!(None, map.content_bounds());
+        map.add_layer(MockLayer::new(None));
+        map.add_layer(MockLayer::new(None));
+        assert_eq!(None, map.content_bounds());
+    }
+    #[test]
+    fn test_pixel_latlon_inverse() {
+        let mut map = Map::new(StereographicProjection::new(LatLon { latitude: Latitude(-40.0), longitude: Longitude(60.0) }), 100, 50, 800, 600);
+        map.set_zoom(1000.0);
+        for &(x, y) in [(100.0, 50.0), (500.0, 350.0), (899.0, 649.0), (123.4, 567.8)].iter() {
+            let pixel = Point { x, y };
+            let back = map.latlon_to_pixel(&map.pixel_to_latlon(&pixel));
+            assert!((back.x - x).abs() < 1e-6);
+            assert!((back.y - y).abs() < 1e-6);
+        }
+    }
+    #[test]
+    fn test_pixel_to_latlon_window_offset() {
+        let mut map = test_map();
+        map.set_geometry(100, 50, 800, 600);
+        // The center of the view is at the center of the map's area of the window
+        assert_eq!(LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) }, map.pixel_to_latlon(&Point { x: 500.0, y: 350.0 }));
+        let ll = LatLon { latitude: Latitude(10.0), longitude: Longitude(-20.0) };
+        assert_eq!(Point { x: 480.0, y: 360.0 }, map.latlon_to_pixel(&ll));
+    }
+    #[test]
+    fn test_fit_content() {
+        let mut map = test_map();
+        let seattle = LatLonRect::from_bounds(Latitude(47.7), Latitude(47.5), Longitude(-122.2), Longitude(-122.5));
+        let portland = LatLonRect::from_bounds(Latitude(45.6), Latitude(45.4), Longitude(-122.5), Longitude(-122.8));
+        map.add_layer(MockLayer::new(Some(seattle.clone())));
+        map.add_layer(MockLayer::new(Some(portland.clone())));
+        assert!(map.fit_content(10));
+        let projection = map.combined_projection();
+        for corner in seattle.corners().iter().chain(portland.corners().iter()) {
+            let pixel = projection.project(corner);
+            assert!(pixel.x >= 10.0 - 1e-6 && pixel.x <= 790.0 + 1e-6);
+            assert!(pixel.y >= 10.0 - 1e-6 && pixel.y <= 590.0 + 1e-6);
+        }
+    }
+    #[test]
+    fn test_fit_content_no_bounds() {
+        let mut map = test_map();
+        map.add_layer(MockLayer::new(None));
+        let camera = map.camera();
+        assert!(!map.fit_content(10));
+        assert_eq!(camera, map.camera());
+    }
+    #[test]
+    fn test_zoom_around() {
+        for &origin in [OriginConvention::LowerLeft, OriginConvention::UpperLeft].iter() {
+            let mut map = Map::new(MercatorProjection::default(), 0, 0, 800, 600);
+            map.set_origin_convention(origin);
+            map.set_zoom(0.001);
+            map.set_view_offset(15.0, -40.0);
+            let pixel = Point { x: 620.0, y: 95.0 };
+            let under = map.combined_projection().unproject(&pixel);
+            map.zoom_around(&pixel, 0.004);
+            assert_eq!(0.004, map.zoom());
+            let after = map.combined_projection().unproject(&pixel);
+            assert!((after.latitude.0 - under.latitude.0).abs() < 1e-9);
+            assert!((after.longitude.0 - under.longitude.0).abs() < 1e-9);
+        }
+    }
+    #[test]
+    fn test_zoom_around_clamped() {
+        let mut map = test_map();
+        map.set_max_zoom(4.0);
+        let pixel = Point { x: 100.0, y: 100.0 };
+        let under = map.combined_projection().unproject(&pixel);
+        map.zoom_around(&pixel, 10.0);
+        assert_eq!(4.0, map.zoom());
+        let after = map.combined_projection().unproject(&pixel);
+        assert!(close_enough(under.latitude.0, after.latitude.0));
+        assert!(close_enough(under.longitude.0, after.longitude.0));
+    }
+    #[test]
+    fn test_remove_middle_layer() {
+        let mut map = test_map();
+        let layers: Vec<MockLayer> = (0..3).map(|_| MockLayer::new(None)).collect();
+        let counters: Vec<_> = layers.iter().map(|layer| layer.draw_counter()).collect();
+        let ids: Vec<LayerId> = layers.into_iter().map(|layer| map.add_layer(layer)).collect();
+        assert!(map.remove_layer(ids[1]));
+        assert!(map.contains_layer(ids[0]));
+        assert!(!map.contains_layer(ids[1]));
+        assert!(map.contains_layer(ids[2]));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec![1, 0, 1], counters.iter().map(|counter| counter.get()).collect::<Vec<_>>());
+    }
+    #[test]
+    fn test_remove_layer_stale_handle() {
+        let mut map = test_map();
+        let id = map.add_layer(MockLayer::new(None));
+        assert!(map.remove_layer(id));
+        assert!(!map.remove_layer(id));
+        // A new layer in the same slot does not make the old handle valid again
+        let replacement = map.add_layer(MockLayer::new(None));
+        assert_ne!(id, replacement);
+        assert!(!map.remove_layer(id));
+        assert!(map.contains_layer(replacement));
+    }
+    #[test]
+    fn test_clear_layers_invalidates_handles() {
+        let mut map = test_map();
+        let first = map.add_layer(MockLayer::new(None));
+        let second = map.add_layer(MockLayer::new(None));
+        map.clear_layers();
+        assert!(!map.remove_layer(first));
+        assert!(!map.remove_layer(second));
+        assert_eq!(None, map.content_bounds());
+    }
+    fn recording_map(names: &[&str]) -> (Map, Vec<LayerId>, Rc<RefCell<Vec<String>>>) {
+        let mut map = test_map();
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let ids = names.iter().map(|name| map.add_layer(MockLayer::recording(name, log.clone()))).collect();
+        (map, ids, log)
+    }
+
+    #[test]
+    fn test_move_layer_draw_order() {
+        let (mut map, ids, log) = recording_map(&["base", "roads", "labels"]);
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["base", "roads", "labels"], *log.borrow());
+
+        log.borrow_mut().clear();
+        assert!(map.move_layer_to_top(ids[0]));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["roads", "labels", "base"], *log.borrow());
+
+        log.borrow_mut().clear();
+        assert!(map.move_layer_to_bottom(ids[2]));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["labels", "roads", "base"], *log.borrow());
+
+        log.borrow_mut().clear();
+        assert!(map.move_layer(ids[0], 1));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["labels", "base", "roads"], *log.borrow());
+    }
+    #[test]
+    fn test_move_layer_past_end() {
+        let (mut map, ids, log) = recording_map(&["base", "roads", "labels"]);
+        assert!(map.move_layer(ids[1], 100));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["base", "labels", "roads"], *log.borrow());
+    }
+    #[test]
+    fn test_move_layer_already_in_place() {
+        let (mut map, ids, log) = recording_map(&["base", "roads"]);
+        assert!(map.move_layer_to_top(ids[1]));
+        assert!(map.move_layer(ids[0], 0));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["base", "roads"], *log.borrow());
+    }
+    #[test]
+    fn test_move_removed_layer() {
+        let (mut map, ids, log) = recording_map(&["base", "roads"]);
+        assert!(map.remove_layer(ids[0]));
+        assert!(!map.move_layer_to_top(ids[0]));
+        assert!(!map.move_layer_to_bottom(ids[0]));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["roads"], *log.borrow());
+    }
+    #[test]
+    fn test_hidden_layer_not_drawn() {
+        let mut map = test_map();
+        let layer = MockLayer::new(None);
+        let counter = layer.draw_counter();
+        let id = map.add_layer(layer);
+        assert!(map.is_layer_visible(id));
+        assert!(map.set_layer_visible(id, false));
+        assert!(!map.is_layer_visible(id));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(0, counter.get());
+        assert!(map.set_layer_visible(id, true));
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(1, counter.get());
+    }
+    #[test]
+    fn test_hidden_layer_keeps_order() {
+        let (mut map, ids, log) = recording_map(&["base", "roads", "labels"]);
+        map.set_layer_visible(ids[1], false);
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["base", "labels"], *log.borrow());
+        log.borrow_mut().clear();
+        map.set_layer_visible(ids[1], true);
+        map.draw(&mut MockCanvas::new());
+        assert_eq!(vec!["base", "roads", "labels"], *log.borrow());
+    }
+    #[test]
+    fn test_visibility_removed_layer() {
+        let mut map = test_map();
+        let id = map.add_layer(MockLayer::new(None));
+        map.set_layer_visible(id, false);
+        map.remove_layer(id);
+        assert!(!map.set_layer_visible(id, true));
+        assert!(!map.is_layer_visible(id));
+        // A layer added in the same slot is visible
+        let replacement = map.add_layer(MockLayer::new(None));
+        assert!(map.is_layer_visible(replacement));
+    }
+    #[test]
+    fn test_suggested_tile_level_bias_up() {
+        let mut map = test_map();
+        map.set_zoom(2f64.powf(2.5));
+        assert_eq!(3, map.suggested_tile_level(1.0));
+    }
+    #[test]
+    fn test_suggested_tile_level_bias_down() {
+        let mut map = test_map();
+        map.set_zoom(2f64.powf(2.5));
+        assert_eq!(2, map.suggested_tile_level(0.0));
+    }
+    #[test]
+    fn test_suggested_tile_level_exact() {
+        let mut map = test_map();
+        map.set_zoom(4.0);
+        assert_eq!(2, map.suggested_tile_level(0.0));
+        assert_eq!(2, map.suggested_tile_level(1.0));
+    }
+}
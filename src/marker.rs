@@ -0,0 +1,119 @@
+use super::{LatLon, LatLonRect};
+use projection::Projection;
+use layer::Layer;
+use canvas::{Canvas, Color};
+
+///
+/// A layer that shows markers at points
+///
+/// Each marker is drawn as a point on the canvas, in the order that the markers were added.
+/// Markers that cannot be projected are skipped. Markers outside the viewport are not skipped,
+/// so the canvas can draw markers that are partly visible.
+///
+pub struct MarkerLayer {
+    /// The positions of the markers
+    markers: Vec<LatLon>,
+    /// The color of the markers
+    color: Color,
+}
+
+impl MarkerLayer {
+    /// Creates a marker layer with no markers, which draws markers in black
+    pub fn new() -> MarkerLayer {
+        MarkerLayer {
+            markers: Vec::new(),
+            color: Color::default(),
+        }
+    }
+
+    /// Adds a marker at a position
+    pub fn add_marker(&mut self, position: LatLon) {
+        self.markers.push(position)
+    }
+    /// Removes and returns the marker at an index, or returns None if there is no marker there
+    pub fn remove_marker(&mut self, index: usize) -> Option<LatLon> {
+        if index < self.markers.len() {
+            Some(self.markers.remove(index))
+        } else {
+            None
+        }
+    }
+    /// Returns the positions of the markers, in the order that they were added
+    pub fn markers(&self) -> &[LatLon] {
+        &self.markers
+    }
+    /// Returns the color of the markers
+    pub fn color(&self) -> Color {
+        self.color
+    }
+    /// Sets the color of the markers
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color
+    }
+}
+
+impl Default for MarkerLayer {
+    fn default() -> Self {
+        MarkerLayer::new()
+    }
+}
+
+impl Layer for MarkerLayer {
+    fn draw(&self, canvas: &mut dyn Canvas, projection: &dyn Projection, _x: i32, _y: i32, _width: i32, _height: i32) {
+        canvas.set_color(self.color);
+        for marker in self.markers.iter() {
+            if let Ok(pixel) = projection.try_project(marker) {
+                if pixel.x.is_finite() && pixel.y.is_finite() {
+                    canvas.draw_point(&pixel);
+                }
+            }
+        }
+    }
+
+    fn bounds(&self) -> Option<LatLonRect> {
+        LatLonRect::from_points(self.markers.iter().cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use equirectangular::EquirectangularProjection;
+    use mock::{CanvasCall, MockCanvas};
+    use ::{Latitude, Longitude, Point};
+
+    #[test]
+    fn test_marker_bounds() {
+        let mut layer = MarkerLayer::new();
+        layer.add_marker(LatLon { latitude: Latitude(47.66), longitude: Longitude(-122.28) });
+        layer.add_marker(LatLon { latitude: Latitude(45.52), longitude: Longitude(-122.68) });
+        layer.add_marker(LatLon { latitude: Latitude(49.28), longitude: Longitude(-123.12) });
+        let expected = LatLonRect::from_bounds(Latitude(49.28), Latitude(45.52), Longitude(-122.28), Longitude(-123.12));
+        assert_eq!(Some(expected), layer.bounds());
+    }
+    #[test]
+    fn test_marker_bounds_empty() {
+        let mut layer = MarkerLayer::new();
+        assert_eq!(None, layer.bounds());
+        layer.add_marker(LatLon { latitude: Latitude(47.66), longitude: Longitude(-122.28) });
+        assert_eq!(Some(LatLon { latitude: Latitude(47.66), longitude: Longitude(-122.28) }), layer.remove_marker(0));
+        assert_eq!(None, layer.remove_marker(0));
+        assert_eq!(None, layer.bounds());
+    }
+    #[test]
+    fn test_marker_draw() {
+        let mut layer = MarkerLayer::new();
+        layer.set_color(Color::rgb(200, 0, 0));
+        layer.add_marker(LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) });
+        layer.add_marker(LatLon { latitude: Latitude(f64::NAN), longitude: Longitude(0.0) });
+        layer.add_marker(LatLon { latitude: Latitude(-5.0), longitude: Longitude(3.0) });
+        assert_eq!(3, layer.markers().len());
+        let mut canvas = MockCanvas::new();
+        layer.draw(&mut canvas, &EquirectangularProjection, 0, 0, 100, 100);
+        assert_eq!(&[
+            CanvasCall::SetColor(Color::rgb(200, 0, 0)),
+            CanvasCall::DrawPoint(Point { x: 20.0, y: 10.0 }),
+            CanvasCall::DrawPoint(Point { x: 3.0, y: -5.0 }),
+        ], canvas.calls());
+    }
+}
@@ -0,0 +1,103 @@
+use std::f64::consts::PI;
+
+use super::{Latitude, Longitude, LatLon, Point, EARTH_MEAN_RADIUS};
+use projection::Projection;
+
+///
+/// A spherical Mercator projection
+///
+/// Map coordinates are distances in the same units as the radius of the sphere, measured along
+/// the equator from its intersection with the prime meridian. The poles project to infinity.
+///
+pub struct MercatorProjection {
+    /// The radius of the sphere
+    radius: f64,
+}
+
+impl MercatorProjection {
+    /// Creates a Mercator projection of a sphere with the provided radius
+    pub fn new(radius: f64) -> MercatorProjection {
+        MercatorProjection {
+            radius,
+        }
+    }
+
+    /// Returns the radius of the sphere
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
+}
+
+impl Default for MercatorProjection {
+    /// Creates a Mercator projection of a sphere with the mean radius of the earth, in meters
+    fn default() -> Self {
+        MercatorProjection::new(EARTH_MEAN_RADIUS)
+    }
+}
+
+impl Projection for MercatorProjection {
+    fn project(&self, position: &LatLon) -> Point<f64> {
+        let x = self.radius * position.longitude.to_radians();
+        let y = self.radius * f64::asinh(f64::tan(position.latitude.to_radians()));
+        Point { x, y }
+    }
+
+    fn unproject(&self, position: &Point<f64>) -> LatLon {
+        let latitude = f64::atan(f64::sinh(position.y / self.radius));
+        let longitude = position.x / self.radius;
+        LatLon {
+            latitude: Latitude(latitude.to_degrees()),
+            longitude: Longitude(longitude.to_degrees()),
+        }
+    }
+
+    fn horizontal_period(&self) -> Option<f64> {
+        Some(2.0 * PI * self.radius)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use ::close_enough;
+
+    #[test]
+    fn test_mercator_round_trip() {
+        let mercator = MercatorProjection::default();
+        for latitude in (-84..85).map(|i| i as f64 + 0.37) {
+            for longitude in (-18..18).map(|i| i as f64 * 10.0 + 1.3) {
+                let ll = LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+                let unprojected = mercator.unproject(&mercator.project(&ll));
+                assert!(ll.approx_eq(&unprojected, 1e-9));
+            }
+        }
+    }
+    #[test]
+    fn test_mercator_radius() {
+        let mercator = MercatorProjection::new(2.0);
+        assert_eq!(2.0, mercator.radius());
+        let projected = mercator.project(&LatLon { latitude: Latitude(0.0), longitude: Longitude(90.0) });
+        assert!(close_enough(PI, projected.x));
+        assert!(close_enough(0.0, projected.y));
+
+        // One degree of longitude along the equator is one degree of arc in meters
+        let earth = MercatorProjection::default();
+        let degree = earth.project(&LatLon { latitude: Latitude(0.0), longitude: Longitude(1.0) });
+        assert!(close_enough(EARTH_MEAN_RADIUS.to_radians(), degree.x));
+    }
+    #[test]
+    fn test_mercator_conformal() {
+        // North-south and east-west distances are stretched by the same amount
+        let mercator = MercatorProjection::new(1.0);
+        let step = 1e-6;
+        let at = LatLon { latitude: Latitude(60.0), longitude: Longitude(10.0) };
+        let origin = mercator.project(&at);
+        let north = mercator.project(&LatLon { latitude: Latitude(60.0 + step), longitude: Longitude(10.0) });
+        let east = mercator.project(&LatLon { latitude: Latitude(60.0), longitude: Longitude(10.0 + step) });
+        let north_scale = (north.y - origin.y) / step.to_radians();
+        let east_scale = (east.x - origin.x) / (step.to_radians() * 60f64.to_radians().cos());
+        assert!((north_scale / east_scale - 1.0).abs() < 1e-4);
+        assert!(close_enough(2.0, north_scale));
+    }
+}
@@ -2,23 +2,75 @@ use super::{Latitude, Longitude, LatLon, Point};
 use projection::Projection;
 
 ///
-/// A Millier cylindrical projection implementation
+/// A Miller cylindrical projection implementation
+///
+/// Map coordinates are in degrees: the X coordinate is the longitude, and the Y coordinate is
+/// the Miller transformation of the latitude, which is 0 at the equator and about 132 at
+/// 90 degrees north.
 ///
 pub struct MillerCylindricalProjection;
 
 impl Projection for MillerCylindricalProjection {
     fn project(&self, position: &LatLon) -> Point<f64> {
         let x: f64 = position.longitude.into();
-        let latitude: f64 = position.latitude.into();
-        let y = (5.0 / 4.0) * f64::asinh(f64::tan((4.0 / 5.0) * latitude));
-        Point { x: x, y: y }
+        let latitude = position.latitude.to_radians();
+        let y = ((5.0 / 4.0) * f64::asinh(f64::tan((4.0 / 5.0) * latitude))).to_degrees();
+        Point { x, y }
     }
 
     fn unproject(&self, position: &Point<f64>) -> LatLon {
-        let longitude = (5.0 / 4.0) * f64::atan(f64::sinh((4.0 / 5.0) * position.y));
+        let latitude = (5.0 / 4.0) * f64::atan(f64::sinh((4.0 / 5.0) * position.y.to_radians()));
         LatLon {
-            latitude: Latitude(position.y),
-            longitude: Longitude(longitude),
+            latitude: Latitude(latitude.to_degrees()),
+            longitude: Longitude(position.x),
         }
     }
+
+    fn horizontal_period(&self) -> Option<f64> {
+        Some(360.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::close_enough;
+
+    #[test]
+    fn test_miller_identity_1() {
+        let ll = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let projected = MillerCylindricalProjection.project(&ll);
+        let unprojected = MillerCylindricalProjection.unproject(&projected);
+
+        println!("Miller: {:?} => {:?} => {:?}", ll, projected, unprojected);
+        assert!(ll.approx_eq(&unprojected, 1e-9));
+    }
+    #[test]
+    fn test_miller_identity_2() {
+        let ll = LatLon { latitude: Latitude(-33.8688), longitude: Longitude(151.2093) };
+        let projected = MillerCylindricalProjection.project(&ll);
+        let unprojected = MillerCylindricalProjection.unproject(&projected);
+
+        println!("Miller: {:?} => {:?} => {:?}", ll, projected, unprojected);
+        assert!(ll.approx_eq(&unprojected, 1e-9));
+    }
+    #[test]
+    fn test_miller_identity_grid() {
+        for latitude in (-9..10).map(|i| i as f64 * 10.0) {
+            for longitude in (-18..19).map(|i| i as f64 * 10.0) {
+                let ll = LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+                let unprojected = MillerCylindricalProjection.unproject(&MillerCylindricalProjection.project(&ll));
+                assert!(ll.approx_eq(&unprojected, 1e-9));
+            }
+        }
+    }
+    #[test]
+    fn test_miller_known_values() {
+        let equator = MillerCylindricalProjection.project(&LatLon { latitude: Latitude(0.0), longitude: Longitude(30.0) });
+        assert!(close_enough(30.0, equator.x));
+        assert!(close_enough(0.0, equator.y));
+        // The pole is at 2.303 times the radius
+        let pole = MillerCylindricalProjection.project(&LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) });
+        assert!(close_enough(2.303412543, pole.y.to_radians()));
+    }
 }
@@ -0,0 +1,318 @@
+use std::cell::{Cell, RefCell};
+use std::f64;
+use std::rc::Rc;
+
+use super::{Latitude, Longitude, LatLon, LatLonRect, Point};
+use projection::{Projection, ProjectionError};
+use layer::Layer;
+use canvas::{Canvas, Color};
+
+///
+/// A configurable projection for testing code that uses projections
+///
+/// Map coordinates are the latitude and longitude multiplied by a scale, plus an offset:
+/// x = longitude * scale + offset.x and y = latitude * scale + offset.y.
+///
+/// The projection can also be limited to a region, to simulate a projection that cannot represent
+/// the whole earth. Outside the region, project and unproject return NaN coordinates and
+/// try_project and try_unproject return ProjectionError::OutOfBounds.
+///
+pub struct MockProjection {
+    /// The factor that latitudes and longitudes are multiplied by
+    scale: f64,
+    /// The offset added to the scaled coordinates
+    offset: Point<f64>,
+    /// The region outside of which projection fails
+    fail_outside: Option<LatLonRect>,
+}
+
+impl MockProjection {
+    /// Creates a projection with a scale of 1, no offset, and no failure region
+    pub fn new() -> MockProjection {
+        MockProjection {
+            scale: 1.0,
+            offset: Point::origin(),
+            fail_outside: None,
+        }
+    }
+
+    /// Returns the scale
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+    /// Sets the scale
+    pub fn set_scale(&mut self, scale: f64) {
+        self.scale = scale
+    }
+    /// Returns the offset
+    pub fn offset(&self) -> Point<f64> {
+        self.offset.clone()
+    }
+    /// Sets the offset
+    pub fn set_offset(&mut self, offset: Point<f64>) {
+        self.offset = offset
+    }
+    /// Returns the region outside of which projection fails
+    pub fn fail_outside(&self) -> Option<LatLonRect> {
+        self.fail_outside.clone()
+    }
+    /// Sets the region outside of which projection fails, or None to make projection always succeed
+    pub fn set_fail_outside(&mut self, region: Option<LatLonRect>) {
+        self.fail_outside = region
+    }
+
+    /// Returns true if a position is in the region where projection succeeds
+    fn in_region(&self, position: &LatLon) -> bool {
+        match self.fail_outside {
+            Some(ref region) => position.latitude >= region.south() && position.latitude <= region.north()
+                && position.longitude >= region.west() && position.longitude <= region.east(),
+            None => true,
+        }
+    }
+
+    fn project_unchecked(&self, position: &LatLon) -> Point<f64> {
+        let x: f64 = position.longitude.into();
+        let y: f64 = position.latitude.into();
+        Point { x: x * self.scale + self.offset.x, y: y * self.scale + self.offset.y }
+    }
+    fn unproject_unchecked(&self, position: &Point<f64>) -> LatLon {
+        LatLon {
+            latitude: Latitude((position.y - self.offset.y) / self.scale),
+            longitude: Longitude((position.x - self.offset.x) / self.scale),
+        }
+    }
+}
+
+impl Default for MockProjection {
+    fn default() -> Self {
+        MockProjection::new()
+    }
+}
+
+impl Projection for MockProjection {
+    fn project(&self, position: &LatLon) -> Point<f64> {
+        if self.in_region(position) {
+            self.project_unchecked(position)
+        } else {
+            Point { x: f64::NAN, y: f64::NAN }
+        }
+    }
+    fn unproject(&self, position: &Point<f64>) -> LatLon {
+        let unprojected = self.unproject_unchecked(position);
+        if self.in_region(&unprojected) {
+            unprojected
+        } else {
+            LatLon { latitude: Latitude(f64::NAN), longitude: Longitude(f64::NAN) }
+        }
+    }
+
+    fn try_project(&self, position: &LatLon) -> Result<Point<f64>, ProjectionError> {
+        if !(position.latitude.0.is_finite() && position.longitude.0.is_finite()) {
+            Err(ProjectionError::InvalidInput)
+        } else if !self.in_region(position) {
+            Err(ProjectionError::OutOfBounds)
+        } else {
+            Ok(self.project_unchecked(position))
+        }
+    }
+    fn try_unproject(&self, position: &Point<f64>) -> Result<LatLon, ProjectionError> {
+        if !(position.x.is_finite() && position.y.is_finite()) {
+            return Err(ProjectionError::InvalidInput);
+        }
+        let unprojected = self.unproject_unchecked(position);
+        if self.in_region(&unprojected) {
+            Ok(unprojected)
+        } else {
+            Err(ProjectionError::OutOfBounds)
+        }
+    }
+}
+
+///
+/// A layer for testing code that uses layers
+///
+/// The layer does not draw anything. It reports the bounds that it was created with, and counts
+/// the number of times that it has been drawn. A layer created with MockLayer::recording also
+/// adds its name to a shared log each time it is drawn, so tests can check the drawing order
+/// of several layers.
+///
+pub struct MockLayer {
+    /// The bounds that this layer reports
+    bounds: Option<LatLonRect>,
+    /// The number of times this layer has been drawn, shared with draw_counter
+    draws: Rc<Cell<usize>>,
+    /// The name of this layer and the log to add it to when drawn
+    log: Option<(String, Rc<RefCell<Vec<String>>>)>,
+}
+
+impl MockLayer {
+    /// Creates a layer that reports the provided bounds
+    pub fn new(bounds: Option<LatLonRect>) -> MockLayer {
+        MockLayer {
+            bounds,
+            draws: Rc::new(Cell::new(0)),
+            log: None,
+        }
+    }
+    /// Creates a layer with no bounds that adds its name to a log each time it is drawn
+    pub fn recording(name: &str, log: Rc<RefCell<Vec<String>>>) -> MockLayer {
+        MockLayer {
+            log: Some((name.to_owned(), log)),
+            ..MockLayer::new(None)
+        }
+    }
+    ///
+    /// Returns a counter of the number of times this layer has been drawn
+    ///
+    /// The counter keeps counting after the layer is moved into a map.
+    ///
+    pub fn draw_counter(&self) -> Rc<Cell<usize>> {
+        self.draws.clone()
+    }
+}
+
+impl Layer for MockLayer {
+    fn draw(&self, _canvas: &mut dyn Canvas, _projection: &dyn Projection, _x: i32, _y: i32, _width: i32, _height: i32) {
+        self.draws.set(self.draws.get() + 1);
+        if let Some((ref name, ref log)) = self.log {
+            log.borrow_mut().push(name.clone());
+        }
+    }
+    fn bounds(&self) -> Option<LatLonRect> {
+        self.bounds.clone()
+    }
+}
+
+///
+/// A drawing operation that a MockCanvas recorded
+///
+#[derive(Debug,Clone,PartialEq)]
+pub enum CanvasCall {
+    SetColor(Color),
+    DrawPoint(Point<f64>),
+    DrawLine(Point<f64>, Point<f64>),
+    FillPolygon(Vec<Point<f64>>),
+}
+
+///
+/// A canvas for testing layers, which records the drawing operations that it receives
+/// instead of drawing
+///
+#[derive(Debug,Default)]
+pub struct MockCanvas {
+    /// The operations, in the order that they were received
+    calls: Vec<CanvasCall>,
+}
+
+impl MockCanvas {
+    /// Creates a canvas that has not received any operations
+    pub fn new() -> MockCanvas {
+        MockCanvas { calls: Vec::new() }
+    }
+    /// Returns the operations that this canvas has received, in order
+    pub fn calls(&self) -> &[CanvasCall] {
+        &self.calls
+    }
+    /// Forgets all operations that this canvas has received
+    pub fn clear(&mut self) {
+        self.calls.clear()
+    }
+}
+
+impl Canvas for MockCanvas {
+    fn set_color(&mut self, color: Color) {
+        self.calls.push(CanvasCall::SetColor(color))
+    }
+    fn draw_point(&mut self, point: &Point<f64>) {
+        self.calls.push(CanvasCall::DrawPoint(point.clone()))
+    }
+    fn draw_line(&mut self, start: &Point<f64>, end: &Point<f64>) {
+        self.calls.push(CanvasCall::DrawLine(start.clone(), end.clone()))
+    }
+    fn fill_polygon(&mut self, points: &[Point<f64>]) {
+        self.calls.push(CanvasCall::FillPolygon(points.to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limited() -> MockProjection {
+        let mut projection = MockProjection::new();
+        projection.set_scale(2.0);
+        projection.set_offset(Point { x: 10.0, y: -10.0 });
+        projection.set_fail_outside(Some(LatLonRect::from_bounds(Latitude(10.0), Latitude(-10.0), Longitude(20.0), Longitude(-20.0))));
+        projection
+    }
+
+    #[test]
+    fn test_mock_scale_offset() {
+        let projection = limited();
+        let ll = LatLon { latitude: Latitude(5.0), longitude: Longitude(-3.0) };
+        let projected = projection.project(&ll);
+        assert_eq!(Point { x: 4.0, y: 0.0 }, projected);
+        assert_eq!(ll, projection.unproject(&projected));
+    }
+    #[test]
+    fn test_mock_inside_region() {
+        let projection = limited();
+        let edge = LatLon { latitude: Latitude(10.0), longitude: Longitude(-20.0) };
+        assert_eq!(Ok(Point { x: -30.0, y: 10.0 }), projection.try_project(&edge));
+    }
+    #[test]
+    fn test_mock_outside_region() {
+        let projection = limited();
+        let outside = LatLon { latitude: Latitude(11.0), longitude: Longitude(0.0) };
+        assert_eq!(Err(ProjectionError::OutOfBounds), projection.try_project(&outside));
+        assert!(projection.project(&outside).x.is_nan());
+        let far = Point { x: 1000.0, y: 0.0 };
+        assert_eq!(Err(ProjectionError::OutOfBounds), projection.try_unproject(&far));
+        assert!(projection.unproject(&far).latitude.0.is_nan());
+    }
+    #[test]
+    fn test_mock_no_region() {
+        let projection = MockProjection::new();
+        let ll = LatLon { latitude: Latitude(80.0), longitude: Longitude(170.0) };
+        assert_eq!(Ok(Point { x: 170.0, y: 80.0 }), projection.try_project(&ll));
+    }
+    #[test]
+    fn test_mock_layer_draw_counter() {
+        let layer = MockLayer::new(None);
+        let counter = layer.draw_counter();
+        let mut canvas = MockCanvas::new();
+        layer.draw(&mut canvas, &MockProjection::new(), 0, 0, 10, 10);
+        layer.draw(&mut canvas, &MockProjection::new(), 0, 0, 10, 10);
+        assert_eq!(2, counter.get());
+        assert_eq!(None, layer.bounds());
+    }
+    #[test]
+    fn test_mock_layer_recording() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let first = MockLayer::recording("first", log.clone());
+        let second = MockLayer::recording("second", log.clone());
+        let mut canvas = MockCanvas::new();
+        second.draw(&mut canvas, &MockProjection::new(), 0, 0, 10, 10);
+        first.draw(&mut canvas, &MockProjection::new(), 0, 0, 10, 10);
+        second.draw(&mut canvas, &MockProjection::new(), 0, 0, 10, 10);
+        assert_eq!(vec!["second", "first", "second"], *log.borrow());
+        assert_eq!(2, second.draw_counter().get());
+        assert_eq!(None, first.bounds());
+    }
+    #[test]
+    fn test_mock_canvas_records() {
+        let mut canvas = MockCanvas::new();
+        canvas.set_color(Color::rgb(255, 0, 0));
+        canvas.draw_point(&Point { x: 1.0, y: 2.0 });
+        canvas.draw_line(&Point { x: 1.0, y: 2.0 }, &Point { x: 3.0, y: 4.0 });
+        canvas.fill_polygon(&[Point::origin(), Point { x: 1.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }]);
+        assert_eq!(&[
+            CanvasCall::SetColor(Color::rgb(255, 0, 0)),
+            CanvasCall::DrawPoint(Point { x: 1.0, y: 2.0 }),
+            CanvasCall::DrawLine(Point { x: 1.0, y: 2.0 }, Point { x: 3.0, y: 4.0 }),
+            CanvasCall::FillPolygon(vec![Point::origin(), Point { x: 1.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }]),
+        ], canvas.calls());
+        canvas.clear();
+        assert!(canvas.calls().is_empty());
+    }
+}
@@ -0,0 +1,386 @@
+use std::f64::consts::PI;
+
+use super::{Latitude, Longitude, LatLon, Polygon, normalize_longitude};
+use sphere::Vector3;
+
+///
+/// Returns the point reached by traveling a distance from a start point along a rhumb line
+/// (a line of constant bearing)
+///
+/// bearing_degrees is measured clockwise from true north. distance and radius must use the same
+/// units. A path that would pass a pole stops at the pole.
+///
+pub fn rhumb_destination(start: &LatLon, bearing_degrees: f64, distance: f64, radius: f64) -> LatLon {
+    let angular_distance = distance / radius;
+    let bearing = bearing_degrees.to_radians();
+    let start_latitude = start.latitude.to_radians();
+
+    let delta_latitude = angular_distance * bearing.cos();
+    let end_latitude = (start_latitude + delta_latitude).clamp(-PI / 2.0, PI / 2.0);
+
+    // The ratio of the latitude change to the change in Mercator y coordinate, which
+    // relates east-west distance to change in longitude along the line
+    let delta_mercator = f64::ln(f64::tan(PI / 4.0 + end_latitude / 2.0) / f64::tan(PI / 4.0 + start_latitude / 2.0));
+    let q = if delta_mercator.abs() > 1e-12 {
+        (end_latitude - start_latitude) / delta_mercator
+    } else {
+        start_latitude.cos()
+    };
+    // At a pole, every longitude is the same point
+    let delta_longitude = if q.abs() > 1e-12 { angular_distance * bearing.sin() / q } else { 0.0 };
+
+    LatLon {
+        latitude: Latitude(end_latitude.to_degrees()),
+        longitude: normalize_longitude(start.longitude + Longitude(delta_longitude.to_degrees())),
+    }
+}
+
+///
+/// Predicts the position after traveling from a start point at a constant bearing and speed
+///
+/// The path follows a rhumb line. bearing_degrees is measured clockwise from true north.
+/// speed_mps is in meters per second and radius is the radius of the earth in meters.
+///
+pub fn dead_reckon(start: &LatLon, bearing_degrees: f64, speed_mps: f64, seconds: f64, radius: f64) -> LatLon {
+    rhumb_destination(start, bearing_degrees, speed_mps * seconds, radius)
+}
+
+/// Differences in bearing smaller than this, in degrees, are reported as Turn::Straight
+const STRAIGHT_TOLERANCE: f64 = 2.0;
+
+/// The largest number of intervals that great_circle_waypoints divides a great circle into
+pub const MAX_WAYPOINTS: usize = 1_000_000;
+
+///
+/// A direction to turn
+///
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub enum Turn {
+    /// Turn counterclockwise
+    Left,
+    /// Turn clockwise
+    Right,
+    /// Keep the current bearing
+    Straight,
+}
+
+///
+/// Returns the signed difference between two bearings, in degrees
+///
+/// The result is in the range (-180, 180]. It is positive if the shortest rotation from `from` to
+/// `to` is clockwise.
+///
+pub fn angle_difference(from: f64, to: f64) -> f64 {
+    let difference = (to - from) % 360.0;
+    if difference > 180.0 {
+        difference - 360.0
+    } else if difference <= -180.0 {
+        difference + 360.0
+    } else {
+        difference
+    }
+}
+
+///
+/// Returns the direction to turn to change from the current bearing to the target bearing,
+/// and the angle to turn in degrees
+///
+/// Turns of less than 2 degrees are reported as Turn::Straight.
+///
+pub fn turn_direction(current_bearing: f64, target_bearing: f64) -> (Turn, f64) {
+    let difference = angle_difference(current_bearing, target_bearing);
+    let turn = if difference.abs() < STRAIGHT_TOLERANCE {
+        Turn::Straight
+    } else if difference > 0.0 {
+        Turn::Right
+    } else {
+        Turn::Left
+    };
+    (turn, difference.abs())
+}
+
+///
+/// Returns the bearing of a path, in degrees clockwise from true north, at a fraction of the
+/// path's total length
+///
+/// Each pair of consecutive points is connected by a great circle, so the bearing changes
+/// gradually along each segment and changes suddenly at the points where segments meet.
+/// A fraction of 0 gives the bearing leaving the first point, and a fraction of 1 gives the
+/// bearing arriving at the last point. Fractions outside that range are clamped.
+///
+/// This returns None if the path has fewer than two distinct points.
+///
+pub fn path_bearing_at(points: &[LatLon], fraction: f64) -> Option<f64> {
+    let vectors: Vec<Vector3> = points.iter().map(Vector3::from_latlon).collect();
+    let lengths: Vec<f64> = vectors.windows(2).map(|pair| pair[0].angle_to(pair[1])).collect();
+    let total: f64 = lengths.iter().sum();
+    if total == 0.0 {
+        return None;
+    }
+
+    let mut remaining = fraction.clamp(0.0, 1.0) * total;
+    for (i, &length) in lengths.iter().enumerate() {
+        if length == 0.0 {
+            continue;
+        }
+        let last_segment = lengths[i + 1..].iter().all(|&length| length == 0.0);
+        if remaining < length || last_segment {
+            let start = &points[i];
+            let end = &points[i + 1];
+            let t = (remaining / length).min(1.0);
+            return Some(if t < 1.0 {
+                let position = vectors[i].slerp(vectors[i + 1], t).to_latlon();
+                position.bearing_to(end)
+            } else {
+                // Arriving at the end, the bearing is opposite the bearing back to the start
+                (end.bearing_to(start) + 180.0) % 360.0
+            });
+        }
+        remaining -= length;
+    }
+    None
+}
+
+///
+/// Returns points along the great circle from start to end, spaced interval_meters apart
+///
+/// The first point is start. If end is not an exact multiple of the interval away from start,
+/// it is added as the last point. radius is the radius of the earth in meters. If the interval is
+/// not positive, or is so small that the path would have more than MAX_WAYPOINTS intervals, this
+/// returns only start and end.
+///
+/// For antipodal points, the waypoints follow the same great circle as LatLon::interpolate.
+///
+pub fn great_circle_waypoints(start: &LatLon, end: &LatLon, interval_meters: f64, radius: f64) -> Vec<LatLon> {
+    let total = Vector3::from_latlon(start).angle_to(Vector3::from_latlon(end)) * radius;
+    if interval_meters.is_nan() || interval_meters <= 0.0 || total == 0.0 || total / interval_meters > MAX_WAYPOINTS as f64 {
+        return vec![start.clone(), end.clone()];
+    }
+    let steps = (total / interval_meters).floor() as usize;
+    let mut waypoints: Vec<LatLon> = (0..(steps + 1))
+        .map(|i| start.interpolate(end, i as f64 * interval_meters / total))
+        .collect();
+    if (steps as f64) * interval_meters < total {
+        waypoints.push(end.clone());
+    }
+    waypoints
+}
+
+///
+/// Returns a polygon approximating the circle of points at a distance from a center point
+///
+/// The polygon has segments points at evenly spaced bearings, going clockwise from north.
+/// radius_meters is along the earth's surface, and radius_earth is the radius of the earth
+/// in meters.
+///
+/// The polygon's edges are straight in latitude and longitude. Longitudes continue past
+/// 180 or -180 when the circle crosses the antimeridian, so the ring does not jump across
+/// the map. If the circle goes around a pole, the polygon follows the circle from -180 to
+/// 180 degrees of longitude, with extra points where it crosses the antimeridian, and is
+/// closed along the pole. Circles large enough to go around both poles are not supported.
+///
+pub fn geodesic_buffer(center: &LatLon, radius_meters: f64, segments: usize, radius_earth: f64) -> Polygon<LatLon> {
+    let ring: Vec<LatLon> = (0..segments)
+        .map(|i| {
+            let position = center.destination(360.0 * i as f64 / segments as f64, radius_meters, radius_earth);
+            // Keep the longitude continuous with the center
+            LatLon {
+                latitude: position.latitude,
+                longitude: center.longitude + normalize_longitude(position.longitude - center.longitude),
+            }
+        })
+        .collect();
+
+    let angular_radius = (radius_meters / radius_earth).to_degrees();
+    let around_north = 90.0 - center.latitude.0 < angular_radius;
+    let around_south = center.latitude.0 + 90.0 < angular_radius;
+    if around_north == around_south || ring.is_empty() {
+        return Polygon::new(&ring);
+    }
+
+    let mut ring: Vec<LatLon> = ring.into_iter()
+        .map(|position| LatLon { latitude: position.latitude, longitude: normalize_longitude(position.longitude) })
+        .collect();
+    ring.sort_by(|left, right| left.longitude.0.partial_cmp(&right.longitude.0).unwrap());
+    let first = ring[0].clone();
+    let last = ring[ring.len() - 1].clone();
+    // The latitude where the circle crosses the antimeridian, between the last and first points
+    let gap = first.longitude.0 + 360.0 - last.longitude.0;
+    let t = if gap > 0.0 { (180.0 - last.longitude.0) / gap } else { 0.0 };
+    let crossing = Latitude(last.latitude.0 + (first.latitude.0 - last.latitude.0) * t);
+    let pole = Latitude(if around_north { 90.0 } else { -90.0 });
+
+    let mut points = Vec::with_capacity(ring.len() + 4);
+    points.push(LatLon { latitude: crossing, longitude: Longitude(-180.0) });
+    points.extend(ring);
+    points.push(LatLon { latitude: crossing, longitude: Longitude(180.0) });
+    points.push(LatLon { latitude: pole, longitude: Longitude(180.0) });
+    points.push(LatLon { latitude: pole, longitude: Longitude(-180.0) });
+    // Going east along the circle and back west along the north pole is counterclockwise
+    if around_north {
+        points.reverse();
+    }
+    Polygon::new(&points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use geometry::PolygonBuilder;
+    use ::{Point, EARTH_MEAN_RADIUS, close_enough};
+
+    #[test]
+    fn test_dead_reckon_north() {
+        let start = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let end = dead_reckon(&start, 0.0, 10.0, 100.0, EARTH_MEAN_RADIUS);
+        let expected_latitude = 47.6609 + (1000.0 / EARTH_MEAN_RADIUS).to_degrees();
+        assert!((expected_latitude - end.latitude.0).abs() < 1e-9);
+        assert!(close_enough(-122.2816, end.longitude.0));
+    }
+    #[test]
+    fn test_turn_left_across_north() {
+        let (turn, magnitude) = turn_direction(10.0, 350.0);
+        assert_eq!(Turn::Left, turn);
+        assert!(close_enough(20.0, magnitude));
+    }
+    #[test]
+    fn test_turn_right_across_north() {
+        let (turn, magnitude) = turn_direction(350.0, 10.0);
+        assert_eq!(Turn::Right, turn);
+        assert!(close_enough(20.0, magnitude));
+    }
+    #[test]
+    fn test_turn_straight() {
+        assert_eq!(Turn::Straight, turn_direction(90.0, 91.0).0);
+        assert_eq!(Turn::Straight, turn_direction(359.5, 0.5).0);
+    }
+    #[test]
+    fn test_angle_difference_range() {
+        assert!(close_enough(180.0, angle_difference(0.0, 180.0)));
+        assert!(close_enough(180.0, angle_difference(180.0, 0.0)));
+        assert!(close_enough(-90.0, angle_difference(-360.0, 270.0)));
+    }
+    #[test]
+    fn test_path_bearing_at_l_shape() {
+        let path = [
+            LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) },
+            LatLon { latitude: Latitude(10.0), longitude: Longitude(0.0) },
+            LatLon { latitude: Latitude(10.0), longitude: Longitude(10.0) },
+        ];
+        // North along the first segment, then roughly east along the second
+        assert!(close_enough(0.0, path_bearing_at(&path, 0.0).unwrap()));
+        assert!(close_enough(0.0, path_bearing_at(&path, 0.25).unwrap()));
+        let late = path_bearing_at(&path, 0.75).unwrap();
+        let end = path_bearing_at(&path, 1.0).unwrap();
+        assert!((late - 90.0).abs() < 2.0);
+        assert!((end - 90.0).abs() < 2.0);
+        // The great circle along the second segment bulges toward the pole, so its bearing increases
+        assert!(end > late);
+    }
+    #[test]
+    fn test_path_bearing_at_no_length() {
+        let point = LatLon { latitude: Latitude(10.0), longitude: Longitude(10.0) };
+        assert_eq!(None, path_bearing_at(&[], 0.5));
+        assert_eq!(None, path_bearing_at(&[point.clone(), point], 0.5));
+    }
+    #[test]
+    fn test_great_circle_waypoints_spacing() {
+        let seattle = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let tokyo = LatLon { latitude: Latitude(35.6762), longitude: Longitude(139.6503) };
+        let interval = 500_000.0;
+        let waypoints = great_circle_waypoints(&seattle, &tokyo, interval, EARTH_MEAN_RADIUS);
+        assert_eq!(seattle, waypoints[0]);
+        assert_eq!(&tokyo, waypoints.last().unwrap());
+        let distances: Vec<f64> = waypoints.windows(2)
+            .map(|pair| Vector3::from_latlon(&pair[0]).angle_to(Vector3::from_latlon(&pair[1])) * EARTH_MEAN_RADIUS)
+            .collect();
+        for distance in &distances[..distances.len() - 1] {
+            assert!((distance - interval).abs() < 1e-3);
+        }
+        assert!(distances[distances.len() - 1] <= interval);
+    }
+    #[test]
+    fn test_great_circle_waypoints_exact_multiple() {
+        let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        let end = LatLon { latitude: Latitude(0.0), longitude: Longitude(10.0) };
+        let interval = 10f64.to_radians() * EARTH_MEAN_RADIUS / 4.0;
+        let waypoints = great_circle_waypoints(&start, &end, interval, EARTH_MEAN_RADIUS);
+        assert_eq!(5, waypoints.len());
+        assert!(close_enough(10.0, waypoints[4].longitude.0));
+    }
+    #[test]
+    fn test_great_circle_waypoints_antipodal() {
+        let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        let end = start.antipode();
+        let interval = PI * EARTH_MEAN_RADIUS / 4.0;
+        let waypoints = great_circle_waypoints(&start, &end, interval, EARTH_MEAN_RADIUS);
+        assert!(waypoints.len() == 5 || waypoints.len() == 6);
+        assert_eq!(start, waypoints[0]);
+        assert!(waypoints.last().unwrap().approx_eq(&end, 1e-6));
+        for pair in waypoints[..5].windows(2) {
+            assert!((pair[0].distance_to(&pair[1], EARTH_MEAN_RADIUS) - interval).abs() < 1e-3);
+        }
+    }
+    #[test]
+    fn test_great_circle_waypoints_too_many() {
+        let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        let end = LatLon { latitude: Latitude(0.0), longitude: Longitude(90.0) };
+        assert_eq!(vec![start.clone(), end.clone()], great_circle_waypoints(&start, &end, 1e-3, EARTH_MEAN_RADIUS));
+    }
+    #[test]
+    fn test_geodesic_buffer_distance() {
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let buffer = geodesic_buffer(&center, 5_000.0, 36, EARTH_MEAN_RADIUS);
+        assert_eq!(36, buffer.points().len());
+        for point in buffer.points() {
+            assert!((center.distance_to(point, EARTH_MEAN_RADIUS) - 5_000.0).abs() < 1e-3);
+        }
+        assert!(buffer.points()[0].latitude.0 > center.latitude.0);
+    }
+    #[test]
+    fn test_geodesic_buffer_antimeridian() {
+        let center = LatLon { latitude: Latitude(-10.0), longitude: Longitude(179.5) };
+        let buffer = geodesic_buffer(&center, 200_000.0, 24, EARTH_MEAN_RADIUS);
+        // The east side continues past 180 instead of wrapping around to -180
+        assert!(buffer.points().iter().all(|point| (point.longitude.0 - 179.5).abs() < 3.0));
+        assert!(buffer.points().iter().any(|point| point.longitude.0 > 180.0));
+        for point in buffer.points() {
+            assert!((center.distance_to(point, EARTH_MEAN_RADIUS) - 200_000.0).abs() < 1e-3);
+        }
+    }
+    #[test]
+    fn test_geodesic_buffer_around_pole() {
+        let center = LatLon { latitude: Latitude(80.0), longitude: Longitude(30.0) };
+        let radius = 20f64.to_radians() * EARTH_MEAN_RADIUS;
+        let buffer = geodesic_buffer(&center, radius, 72, EARTH_MEAN_RADIUS);
+        let points = buffer.points();
+        assert_eq!(76, points.len());
+        let on_circle = points.iter().filter(|point| (center.distance_to(point, EARTH_MEAN_RADIUS) - radius).abs() < 1e-3).count();
+        assert_eq!(72, on_circle);
+        assert!(points.iter().any(|point| point.latitude.0 == 90.0 && point.longitude.0 == 180.0));
+        assert!(points.iter().any(|point| point.latitude.0 == 90.0 && point.longitude.0 == -180.0));
+        // The ring is simple and clockwise in longitude and latitude
+        let mut builder = PolygonBuilder::new();
+        for point in points {
+            builder.push(Point { x: point.longitude.0, y: point.latitude.0 });
+        }
+        assert!(builder.is_valid());
+        let flat = builder.build().unwrap();
+        assert!(flat.signed_area() < 0.0);
+    }
+    #[test]
+    fn test_rhumb_destination_east_on_equator() {
+        let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(10.0) };
+        let distance = EARTH_MEAN_RADIUS * 20f64.to_radians();
+        let end = rhumb_destination(&start, 90.0, distance, EARTH_MEAN_RADIUS);
+        assert!(close_enough(0.0, end.latitude.0));
+        assert!(close_enough(30.0, end.longitude.0));
+    }
+    #[test]
+    fn test_rhumb_destination_crosses_antimeridian() {
+        let start = LatLon { latitude: Latitude(0.0), longitude: Longitude(170.0) };
+        let distance = EARTH_MEAN_RADIUS * 20f64.to_radians();
+        let end = rhumb_destination(&start, 90.0, distance, EARTH_MEAN_RADIUS);
+        assert!(close_enough(-170.0, end.longitude.0));
+    }
+}
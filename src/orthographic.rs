@@ -0,0 +1,128 @@
+use super::{LatLon, Point};
+use projection::{Projection, ProjectionAspect, azimuthal_aspect, azimuthal_unproject};
+
+///
+/// An orthographic projection, which shows the earth as a globe seen from far away
+///
+/// The earth is a sphere with a radius of 1, and the center projects to the origin. Only the
+/// hemisphere around the center is visible. Positions more than 90 degrees from the center are
+/// behind the globe: project() returns a point with NaN coordinates for them, and try_project()
+/// returns ProjectionError::OutOfBounds. Points outside the globe's outline unproject to NaN the
+/// same way.
+///
+pub struct OrthographicProjection {
+    /// The position at the center of the visible hemisphere
+    center: LatLon,
+}
+
+impl OrthographicProjection {
+    pub fn new(center: LatLon) -> OrthographicProjection {
+        OrthographicProjection {
+            center,
+        }
+    }
+
+    /// Returns the position at the center of the visible hemisphere
+    pub fn center(&self) -> LatLon {
+        self.center.clone()
+    }
+    /// Sets the position at the center of the visible hemisphere
+    pub fn set_center(&mut self, center: LatLon) {
+        self.center = center;
+    }
+}
+
+impl Projection for OrthographicProjection {
+    fn project(&self, position: &LatLon) -> Point<f64> {
+        let center_lat = self.center.latitude.to_radians();
+        let lat = position.latitude.to_radians();
+        let delta_lon = (position.longitude - self.center.longitude).to_radians();
+        // Cosine of the angular distance from the center
+        let cos_c = center_lat.sin() * lat.sin() + center_lat.cos() * lat.cos() * delta_lon.cos();
+        if cos_c < 0.0 {
+            return Point { x: f64::NAN, y: f64::NAN };
+        }
+        let x = lat.cos() * delta_lon.sin();
+        let y = center_lat.cos() * lat.sin() - center_lat.sin() * lat.cos() * delta_lon.cos();
+
+        Point { x, y }
+    }
+    fn unproject(&self, position: &Point<f64>) -> LatLon {
+        // Points outside the outline of the globe have no angular distance and unproject to NaN
+        azimuthal_unproject(&self.center, position, f64::asin)
+    }
+
+    fn natural_origin(&self) -> Option<LatLon> {
+        Some(self.center())
+    }
+    fn set_natural_origin(&mut self, origin: LatLon) -> bool {
+        self.set_center(origin);
+        true
+    }
+    fn aspect(&self) -> ProjectionAspect {
+        azimuthal_aspect(&self.center)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use projection::ProjectionError;
+    use ::{Latitude, Longitude};
+
+    #[test]
+    fn test_orthographic_center() {
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let ortho = OrthographicProjection::new(center.clone());
+        assert!(ortho.project(&center).approx_eq(&Point::origin(), 1e-9));
+        assert!(ortho.unproject(&Point::origin()).approx_eq(&center, 1e-9));
+        assert_eq!(Some(center), ortho.natural_origin());
+    }
+    #[test]
+    fn test_orthographic_identity() {
+        let ortho = OrthographicProjection::new(LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) });
+        for &(latitude, longitude) in [(37.4096, -122.299), (-10.0, -100.0), (80.0, 40.0), (47.0, -170.0)].iter() {
+            let ll = LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+            let projected = ortho.try_project(&ll).unwrap();
+            let unprojected = ortho.try_unproject(&projected).unwrap();
+            println!("Orthographic: {:?} => {:?} => {:?}", ll, projected, unprojected);
+            assert!(ll.approx_eq(&unprojected, 1e-6));
+        }
+    }
+    #[test]
+    fn test_orthographic_known_values() {
+        let ortho = OrthographicProjection::new(LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) });
+        // Points 90 degrees from the center are on the outline of the globe
+        let east = ortho.project(&LatLon { latitude: Latitude(0.0), longitude: Longitude(90.0) });
+        assert!(east.approx_eq(&Point { x: 1.0, y: 0.0 }, 1e-9));
+        let north = ortho.project(&LatLon { latitude: Latitude(90.0), longitude: Longitude(0.0) });
+        assert!(north.approx_eq(&Point { x: 0.0, y: 1.0 }, 1e-9));
+        let halfway = ortho.project(&LatLon { latitude: Latitude(30.0), longitude: Longitude(0.0) });
+        assert!(halfway.approx_eq(&Point { x: 0.0, y: 0.5 }, 1e-9));
+    }
+    #[test]
+    fn test_orthographic_far_side_not_visible() {
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let ortho = OrthographicProjection::new(center.clone());
+        assert_eq!(Err(ProjectionError::OutOfBounds), ortho.try_project(&center.antipode()));
+        let behind = LatLon { latitude: Latitude(-30.0), longitude: Longitude(60.0) };
+        assert_eq!(Err(ProjectionError::OutOfBounds), ortho.try_project(&behind));
+        let projected = ortho.project(&behind);
+        assert!(projected.x.is_nan() && projected.y.is_nan());
+    }
+    #[test]
+    fn test_orthographic_outside_globe() {
+        let ortho = OrthographicProjection::new(LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) });
+        assert_eq!(Err(ProjectionError::OutOfBounds), ortho.try_unproject(&Point { x: 0.8, y: 0.8 }));
+        assert!(ortho.try_unproject(&Point { x: 0.6, y: 0.6 }).is_ok());
+    }
+    #[test]
+    fn test_orthographic_aspect() {
+        let polar = OrthographicProjection::new(LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) });
+        assert_eq!(ProjectionAspect::Normal, polar.aspect());
+        let mut ortho = OrthographicProjection::new(LatLon { latitude: Latitude(0.0), longitude: Longitude(10.0) });
+        assert_eq!(ProjectionAspect::Transverse, ortho.aspect());
+        assert!(ortho.set_natural_origin(LatLon { latitude: Latitude(45.0), longitude: Longitude(10.0) }));
+        assert_eq!(ProjectionAspect::Oblique, ortho.aspect());
+    }
+}
@@ -0,0 +1,161 @@
+use super::{LatLon, LatLonRect, Point, Polygon};
+use projection::Projection;
+use layer::Layer;
+use canvas::{Canvas, Color};
+
+///
+/// A layer that shows polygons, such as country outlines and zones
+///
+/// Each polygon is projected and can be filled, stroked, or both. Filling fills the polygon's
+/// ring on the canvas, and stroking draws a line along each edge of the ring, including the edge
+/// from the last point back to the first point. Polygons with a point that cannot be projected
+/// are skipped.
+///
+pub struct PolygonLayer {
+    /// The polygons
+    polygons: Vec<Polygon<LatLon>>,
+    /// The color to fill polygons with, or None to not fill them
+    fill: Option<Color>,
+    /// The color to stroke polygons with, or None to not stroke them
+    stroke: Option<Color>,
+}
+
+impl PolygonLayer {
+    /// Creates a polygon layer with no polygons, which strokes polygons in black and does not
+    /// fill them
+    pub fn new() -> PolygonLayer {
+        PolygonLayer {
+            polygons: Vec::new(),
+            fill: None,
+            stroke: Some(Color::default()),
+        }
+    }
+
+    /// Adds a polygon
+    pub fn add_polygon(&mut self, polygon: Polygon<LatLon>) {
+        self.polygons.push(polygon)
+    }
+    /// Returns the polygons, in the order that they were added
+    pub fn polygons(&self) -> &[Polygon<LatLon>] {
+        &self.polygons
+    }
+    /// Returns the color that polygons are filled with, or None if they are not filled
+    pub fn fill(&self) -> Option<Color> {
+        self.fill
+    }
+    /// Sets the color that polygons are filled with, or None to not fill them
+    pub fn set_fill(&mut self, fill: Option<Color>) {
+        self.fill = fill
+    }
+    /// Returns the color that polygons are stroked with, or None if they are not stroked
+    pub fn stroke(&self) -> Option<Color> {
+        self.stroke
+    }
+    /// Sets the color that polygons are stroked with, or None to not stroke them
+    pub fn set_stroke(&mut self, stroke: Option<Color>) {
+        self.stroke = stroke
+    }
+}
+
+impl Default for PolygonLayer {
+    fn default() -> Self {
+        PolygonLayer::new()
+    }
+}
+
+impl Layer for PolygonLayer {
+    fn draw(&self, canvas: &mut dyn Canvas, projection: &dyn Projection, _x: i32, _y: i32, _width: i32, _height: i32) {
+        let rings = self.polygons.iter().filter_map(|polygon| {
+            let ring: Vec<Point<f64>> = polygon.points.iter()
+                .map(|point| projection.try_project(point))
+                .collect::<Result<_, _>>()
+                .ok()?;
+            if ring.is_empty() || !ring.iter().all(|pixel| pixel.x.is_finite() && pixel.y.is_finite()) {
+                None
+            } else {
+                Some(ring)
+            }
+        });
+        for ring in rings {
+            if let Some(color) = self.fill {
+                canvas.set_color(color);
+                canvas.fill_polygon(&ring);
+            }
+            if let Some(color) = self.stroke {
+                canvas.set_color(color);
+                for (i, start) in ring.iter().enumerate() {
+                    let end = &ring[(i + 1) % ring.len()];
+                    if start != end {
+                        canvas.draw_line(start, end);
+                    }
+                }
+            }
+        }
+    }
+
+    fn bounds(&self) -> Option<LatLonRect> {
+        self.polygons.iter()
+            .filter_map(|polygon| polygon.bounds())
+            .fold(None, |bounds: Option<LatLonRect>, polygon| match bounds {
+                Some(bounds) => Some(bounds.union(&polygon)),
+                None => Some(polygon),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use equirectangular::EquirectangularProjection;
+    use mock::{CanvasCall, MockCanvas};
+    use ::{Latitude, Longitude};
+
+    fn ll(latitude: f64, longitude: f64) -> LatLon {
+        LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) }
+    }
+
+    #[test]
+    fn test_polygon_layer_bounds() {
+        let mut layer = PolygonLayer::new();
+        assert_eq!(None, layer.bounds());
+        layer.add_polygon(Polygon::new(&[ll(10.0, 0.0), ll(10.0, 10.0), ll(0.0, 10.0), ll(0.0, 0.0)]));
+        layer.add_polygon(Polygon::new(&[ll(-5.0, 20.0), ll(-5.0, 30.0), ll(-15.0, 25.0)]));
+        let bounds = layer.bounds().unwrap();
+        assert_eq!(LatLonRect::from_bounds(Latitude(10.0), Latitude(-15.0), Longitude(30.0), Longitude(0.0)), bounds);
+        for polygon in layer.polygons() {
+            assert!(bounds.contains_rect(&polygon.bounds().unwrap()));
+        }
+    }
+    #[test]
+    fn test_polygon_layer_fill_and_stroke() {
+        let mut layer = PolygonLayer::new();
+        let (red, blue) = (Color::rgb(255, 0, 0), Color::rgb(0, 0, 255));
+        layer.set_fill(Some(red));
+        layer.set_stroke(Some(blue));
+        layer.add_polygon(Polygon::new(&[ll(0.0, 0.0), ll(1.0, 0.0), ll(1.0, 1.0)]));
+        let mut canvas = MockCanvas::new();
+        layer.draw(&mut canvas, &EquirectangularProjection, 0, 0, 100, 100);
+        let (a, b, c) = (Point { x: 0.0, y: 0.0 }, Point { x: 0.0, y: 1.0 }, Point { x: 1.0, y: 1.0 });
+        assert_eq!(&[
+            CanvasCall::SetColor(red),
+            CanvasCall::FillPolygon(vec![a.clone(), b.clone(), c.clone()]),
+            CanvasCall::SetColor(blue),
+            CanvasCall::DrawLine(a.clone(), b.clone()),
+            CanvasCall::DrawLine(b, c.clone()),
+            CanvasCall::DrawLine(c, a),
+        ], canvas.calls());
+    }
+    #[test]
+    fn test_polygon_layer_stroke_only() {
+        let mut layer = PolygonLayer::new();
+        assert_eq!(None, layer.fill());
+        assert_eq!(Some(Color::default()), layer.stroke());
+        // Already closed, and one polygon that cannot be projected
+        layer.add_polygon(Polygon::new(&[ll(0.0, 0.0), ll(1.0, 0.0), ll(1.0, 1.0), ll(0.0, 0.0)]));
+        layer.add_polygon(Polygon::new(&[ll(f64::NAN, 0.0), ll(1.0, 0.0), ll(1.0, 1.0)]));
+        let mut canvas = MockCanvas::new();
+        layer.draw(&mut canvas, &EquirectangularProjection, 0, 0, 100, 100);
+        assert_eq!(3, canvas.calls().iter().filter(|call| matches!(call, CanvasCall::DrawLine(..))).count());
+        assert!(!canvas.calls().iter().any(|call| matches!(call, CanvasCall::FillPolygon(_))));
+    }
+}
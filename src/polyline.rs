@@ -0,0 +1,183 @@
+use super::{Latitude, Longitude, LatLon, LatLonRect, Point};
+use projection::Projection;
+use layer::Layer;
+use canvas::{Canvas, Color};
+
+///
+/// A layer that shows paths, such as GPS tracks and routes
+///
+/// Each segment of each line is projected and drawn as a line on the canvas. Segments with
+/// an end that cannot be projected are skipped.
+///
+/// A segment between two points more than 180 degrees of longitude apart is taken to go the
+/// shorter way around the earth, across the antimeridian. It is drawn as two segments, one
+/// ending at 180 degrees and the other starting at -180 degrees (or the reverse), so that it does
+/// not stretch across the whole map.
+///
+pub struct PolylineLayer {
+    /// The lines, each a list of points
+    lines: Vec<Vec<LatLon>>,
+    /// The color of the lines
+    color: Color,
+}
+
+impl PolylineLayer {
+    /// Creates a polyline layer with no lines, which draws lines in black
+    pub fn new() -> PolylineLayer {
+        PolylineLayer {
+            lines: Vec::new(),
+            color: Color::default(),
+        }
+    }
+
+    /// Adds a line that connects a list of points in order
+    pub fn add_line(&mut self, points: Vec<LatLon>) {
+        self.lines.push(points)
+    }
+    /// Returns the lines, in the order that they were added
+    pub fn lines(&self) -> &[Vec<LatLon>] {
+        &self.lines
+    }
+    /// Returns the color of the lines
+    pub fn color(&self) -> Color {
+        self.color
+    }
+    /// Sets the color of the lines
+    pub fn set_color(&mut self, color: Color) {
+        self.color = color
+    }
+}
+
+impl Default for PolylineLayer {
+    fn default() -> Self {
+        PolylineLayer::new()
+    }
+}
+
+impl Layer for PolylineLayer {
+    fn draw(&self, canvas: &mut dyn Canvas, projection: &dyn Projection, _x: i32, _y: i32, _width: i32, _height: i32) {
+        canvas.set_color(self.color);
+        for line in self.lines.iter() {
+            for pair in line.windows(2) {
+                match antimeridian_crossing(&pair[0], &pair[1]) {
+                    Some((before, after)) => {
+                        draw_projected(canvas, projection, &pair[0], &before);
+                        draw_projected(canvas, projection, &after, &pair[1]);
+                    }
+                    None => draw_projected(canvas, projection, &pair[0], &pair[1]),
+                }
+            }
+        }
+    }
+
+    fn bounds(&self) -> Option<LatLonRect> {
+        LatLonRect::from_points(self.lines.iter().flat_map(|line| line.iter().cloned()))
+    }
+}
+
+/// Projects and draws a segment that does not cross the antimeridian
+fn draw_projected(canvas: &mut dyn Canvas, projection: &dyn Projection, start: &LatLon, end: &LatLon) {
+    let finite = |pixel: &Point<f64>| pixel.x.is_finite() && pixel.y.is_finite();
+    if let (Ok(start), Ok(end)) = (projection.try_project(start), projection.try_project(end)) {
+        if finite(&start) && finite(&end) {
+            canvas.draw_line(&start, &end);
+        }
+    }
+}
+
+///
+/// Returns the points where a segment crosses the antimeridian, on the start's side and then on
+/// the end's side, or None if the ends are at most 180 degrees of longitude apart
+///
+/// The latitude of the crossing is interpolated linearly in latitude and longitude.
+///
+fn antimeridian_crossing(start: &LatLon, end: &LatLon) -> Option<(LatLon, LatLon)> {
+    let difference = end.longitude.0 - start.longitude.0;
+    if difference.abs() <= 180.0 {
+        return None;
+    }
+    // The longitude where the segment leaves the start's side
+    let side = if difference > 0.0 { -180.0 } else { 180.0 };
+    let unwrapped_end = end.longitude.0 - 360.0 * difference.signum();
+    let t = (side - start.longitude.0) / (unwrapped_end - start.longitude.0);
+    let latitude = Latitude(start.latitude.0 + (end.latitude.0 - start.latitude.0) * t);
+    Some((LatLon { latitude, longitude: Longitude(side) }, LatLon { latitude, longitude: Longitude(-side) }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use equirectangular::EquirectangularProjection;
+    use mock::{CanvasCall, MockCanvas};
+    use ::close_enough;
+
+    fn ll(latitude: f64, longitude: f64) -> LatLon {
+        LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) }
+    }
+
+    fn drawn_segments(layer_lines: Vec<Vec<LatLon>>) -> Vec<(Point<f64>, Point<f64>)> {
+        let mut layer = PolylineLayer::new();
+        for line in layer_lines {
+            layer.add_line(line);
+        }
+        let mut canvas = MockCanvas::new();
+        layer.draw(&mut canvas, &EquirectangularProjection, 0, 0, 100, 100);
+        assert_eq!(CanvasCall::SetColor(Color::default()), canvas.calls()[0]);
+        canvas.calls()[1..].iter()
+            .map(|call| match *call {
+                CanvasCall::DrawLine(ref start, ref end) => (start.clone(), end.clone()),
+                ref other => panic!("Unexpected call {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_polyline_bounds() {
+        let mut layer = PolylineLayer::new();
+        assert_eq!(None, layer.bounds());
+        layer.add_line(vec![ll(47.66, -122.28), ll(47.60, -122.33), ll(47.45, -122.30)]);
+        layer.add_line(vec![ll(45.52, -122.68), ll(45.60, -122.50)]);
+        let expected = LatLonRect::from_bounds(Latitude(47.66), Latitude(45.52), Longitude(-122.28), Longitude(-122.68));
+        assert_eq!(Some(expected), layer.bounds());
+        assert_eq!(2, layer.lines().len());
+    }
+    #[test]
+    fn test_polyline_color() {
+        let mut layer = PolylineLayer::new();
+        layer.set_color(Color::rgba(0, 0, 255, 128));
+        layer.add_line(vec![ll(0.0, 0.0), ll(1.0, 1.0)]);
+        let mut canvas = MockCanvas::new();
+        layer.draw(&mut canvas, &EquirectangularProjection, 0, 0, 100, 100);
+        assert_eq!(&[
+            CanvasCall::SetColor(Color::rgba(0, 0, 255, 128)),
+            CanvasCall::DrawLine(Point { x: 0.0, y: 0.0 }, Point { x: 1.0, y: 1.0 }),
+        ], canvas.calls());
+    }
+    #[test]
+    fn test_polyline_draw_segments() {
+        let segments = drawn_segments(vec![vec![ll(0.0, 0.0), ll(1.0, 2.0), ll(3.0, 4.0)], vec![ll(5.0, 5.0)]]);
+        assert_eq!(vec![
+            (Point { x: 0.0, y: 0.0 }, Point { x: 2.0, y: 1.0 }),
+            (Point { x: 2.0, y: 1.0 }, Point { x: 4.0, y: 3.0 }),
+        ], segments);
+    }
+    #[test]
+    fn test_polyline_split_at_antimeridian() {
+        let segments = drawn_segments(vec![vec![ll(10.0, 170.0), ll(20.0, -170.0)]]);
+        assert_eq!(2, segments.len());
+        assert_eq!(Point { x: 170.0, y: 10.0 }, segments[0].0);
+        assert!(close_enough(180.0, segments[0].1.x));
+        assert!(close_enough(15.0, segments[0].1.y));
+        assert!(close_enough(-180.0, segments[1].0.x));
+        assert!(close_enough(15.0, segments[1].0.y));
+        assert_eq!(Point { x: -170.0, y: 20.0 }, segments[1].1);
+    }
+    #[test]
+    fn test_polyline_split_westward() {
+        let segments = drawn_segments(vec![vec![ll(0.0, -175.0), ll(-15.0, 165.0)]]);
+        assert_eq!(2, segments.len());
+        assert!(close_enough(-180.0, segments[0].1.x));
+        assert!(close_enough(-3.75, segments[0].1.y));
+        assert!(close_enough(180.0, segments[1].0.x));
+    }
+}
@@ -1,5 +1,8 @@
+use std::error::Error;
+use std::fmt;
 
-use super::{Point, LatLon, Polygon};
+use super::{Point, Latitude, Longitude, LatLon, LatLonRect, Polygon, normalize_latitude, normalize_longitude};
+use sphere::Vector3;
 
 /// A trait for projections
 pub trait Projection {
@@ -8,12 +11,369 @@ pub trait Projection {
     /// Unprojects a point from map coordinates to latitude/longitude
     fn unproject(&self, position: &Point<f64>) -> LatLon;
 
+    /// Projects a latitude/longitude point into map coordinates, returning an error if the
+    /// latitude or longitude is NaN or infinite, or if the projected point is not finite
+    ///
+    /// A non-finite result means that the position is at a singularity of the projection, and
+    /// returns ProjectionError::OutOfBounds.
+    fn try_project(&self, position: &LatLon) -> Result<Point<f64>, ProjectionError> {
+        if !(position.latitude.0.is_finite() && position.longitude.0.is_finite()) {
+            return Err(ProjectionError::InvalidInput);
+        }
+        let projected = self.project(position);
+        if projected.x.is_finite() && projected.y.is_finite() {
+            Ok(projected)
+        } else {
+            Err(ProjectionError::OutOfBounds)
+        }
+    }
+    /// Unprojects a point from map coordinates to latitude/longitude, returning an error if
+    /// either coordinate is NaN or infinite, or if the unprojected position is not finite
+    ///
+    /// A non-finite result means that the point is outside the area that the projection covers,
+    /// and returns ProjectionError::OutOfBounds.
+    fn try_unproject(&self, position: &Point<f64>) -> Result<LatLon, ProjectionError> {
+        if !(position.x.is_finite() && position.y.is_finite()) {
+            return Err(ProjectionError::InvalidInput);
+        }
+        let unprojected = self.unproject(position);
+        if unprojected.latitude.0.is_finite() && unprojected.longitude.0.is_finite() {
+            Ok(unprojected)
+        } else {
+            Err(ProjectionError::OutOfBounds)
+        }
+    }
+
+    /// Returns the great-circle distance, in degrees of arc, between a point and the result of
+    /// projecting and then unprojecting it
+    ///
+    /// This is close to zero where the projection is numerically stable and grows near its
+    /// singularities. If the round trip does not produce a finite position, this returns infinity.
+    fn roundtrip_error(&self, point: &LatLon) -> f64 {
+        let roundtrip = self.unproject(&self.project(point));
+        if !(roundtrip.latitude.0.is_finite() && roundtrip.longitude.0.is_finite()) {
+            return f64::INFINITY;
+        }
+        Vector3::from_latlon(point).angle_to(Vector3::from_latlon(&roundtrip)).to_degrees()
+    }
+
+    /// Returns the point that this projection is defined around, if it has one
+    ///
+    /// For azimuthal projections, this is the point that the projection is built from. That
+    /// is the center of the map for orthographic and gnomonic projections, and the projection
+    /// point, opposite the center of the map, for stereographic projections. Projections that
+    /// are defined the same way everywhere along the equator, like cylindrical projections,
+    /// return None.
+    fn natural_origin(&self) -> Option<LatLon> {
+        None
+    }
+    /// Moves the point that this projection is defined around, and returns true if it was moved
+    ///
+    /// Projections that do not have a natural origin return false and do not change.
+    fn set_natural_origin(&mut self, _origin: LatLon) -> bool {
+        false
+    }
+    /// Returns the orientation of this projection relative to the earth's axis
+    fn aspect(&self) -> ProjectionAspect {
+        ProjectionAspect::Normal
+    }
+    /// Returns the width, in map coordinates, of one copy of the world, or None if this
+    /// projection does not repeat horizontally
+    ///
+    /// Cylindrical projections repeat every 360 degrees of longitude, so maps can show copies of
+    /// the world side by side. Azimuthal projections do not repeat and return None.
+    fn horizontal_period(&self) -> Option<f64> {
+        None
+    }
+
     /// Projects a polygon from latitude/longitude into map coordinates
     fn project_poly(&self, poly: &Polygon<LatLon>) -> Polygon<Point<f64>> {
-        poly.points().into_iter().map(|ll| self.project(ll)).collect()
+        poly.points().iter().map(|ll| self.project(ll)).collect()
     }
     /// Unprojects a polyon from map coordinates into latitude/longitude
     fn unproject_poly(&self, poly: &Polygon<Point<f64>>) -> Polygon<LatLon> {
-        poly.points().into_iter().map(|point| self.unproject(point)).collect()
+        poly.points().iter().map(|point| self.unproject(point)).collect()
+    }
+}
+
+///
+/// Projects a rectangle with one projection and unprojects it with another, returning the
+/// smallest rectangle that contains the result
+///
+/// Edges of the rectangle can curve when projected, so the extremes are not always at the
+/// corners. Each edge is sampled at samples evenly spaced points, starting at a corner.
+/// With one sample per edge, only the corners are used. Points that do not reproject to finite
+/// positions are ignored.
+///
+pub fn reproject_bounds(from: &dyn Projection, to: &dyn Projection, rect: &LatLonRect, samples: usize) -> LatLonRect {
+    let samples = samples.max(1);
+    let corners = rect.corners();
+    let mut north = f64::NAN;
+    let mut south = f64::NAN;
+    let mut east = f64::NAN;
+    let mut west = f64::NAN;
+    for (i, start) in corners.iter().enumerate() {
+        let end = &corners[(i + 1) % corners.len()];
+        for sample in 0..samples {
+            let t = sample as f64 / samples as f64;
+            let position = LatLon {
+                latitude: Latitude(start.latitude.0 + (end.latitude.0 - start.latitude.0) * t),
+                longitude: Longitude(start.longitude.0 + (end.longitude.0 - start.longitude.0) * t),
+            };
+            let reprojected = to.unproject(&from.project(&position));
+            if reprojected.latitude.0.is_finite() && reprojected.longitude.0.is_finite() {
+                north = north.max(reprojected.latitude.0);
+                south = south.min(reprojected.latitude.0);
+                east = east.max(reprojected.longitude.0);
+                west = west.min(reprojected.longitude.0);
+            }
+        }
+    }
+    LatLonRect::from_bounds(Latitude(north), Latitude(south), Longitude(east), Longitude(west))
+}
+
+///
+/// Returns the Tissot indicatrix of a projection at a point: a small circle on the sphere,
+/// projected into map coordinates
+///
+/// The circle has a radius of radius_degrees of arc around center and is approximated with
+/// segments points, starting north of the center and going clockwise. The shape of the result
+/// shows how the projection stretches and skews distances near the center. The points are
+/// found with LatLon::destination, so their longitudes are normalized to [-180, 180].
+///
+pub fn tissot_indicatrix(projection: &dyn Projection, center: &LatLon, radius_degrees: f64, segments: usize) -> Polygon<Point<f64>> {
+    (0..segments)
+        .map(|i| {
+            let bearing = 360.0 * i as f64 / segments as f64;
+            projection.project(&center.destination(bearing, radius_degrees.to_radians(), 1.0))
+        })
+        .collect()
+}
+
+///
+/// Unprojects a point from the plane of an azimuthal projection that is centered on a position
+/// and maps a distance from the origin to an angular distance from the center
+///
+/// angular_distance takes the distance of the point from the origin and returns the angular
+/// distance of the position from the center, in radians. This is the only part of the inverse
+/// that differs between azimuthal projections. The origin unprojects to the center.
+///
+pub(crate) fn azimuthal_unproject<F>(center: &LatLon, position: &Point<f64>, angular_distance: F) -> LatLon
+    where F: Fn(f64) -> f64 {
+    // Convert to polar coordinates
+    let rho = f64::hypot(position.x, position.y);
+    if rho == 0.0 {
+        return center.clone();
+    }
+    let c = angular_distance(rho);
+    let center_lat = center.latitude.to_radians();
+    // Unproject
+    let lat_radians = f64::asin(c.cos() * center_lat.sin() + position.y * c.sin() * center_lat.cos() / rho);
+    let delta_lon_radians = f64::atan2(position.x * c.sin(), rho * center_lat.cos() * c.cos() - position.y * center_lat.sin() * c.sin());
+    // Convert to lat/lon
+    let latitude = normalize_latitude(Latitude(lat_radians.to_degrees()));
+    let longitude = normalize_longitude(Longitude(delta_lon_radians.to_degrees()) + center.longitude);
+    LatLon {
+        latitude,
+        longitude,
+    }
+}
+
+///
+/// Returns the aspect of an azimuthal projection that is defined around a position
+///
+/// The aspect is normal at the poles, transverse on the equator, and oblique everywhere else.
+///
+pub(crate) fn azimuthal_aspect(origin: &LatLon) -> ProjectionAspect {
+    let latitude = origin.latitude.0.abs();
+    if (latitude - 90.0).abs() < 1e-9 {
+        ProjectionAspect::Normal
+    } else if latitude < 1e-9 {
+        ProjectionAspect::Transverse
+    } else {
+        ProjectionAspect::Oblique
+    }
+}
+
+/// The orientation of a projection relative to the earth's axis
+#[derive(Debug,Copy,Clone,PartialEq,Eq)]
+pub enum ProjectionAspect {
+    /// The usual orientation: aligned with the equator for cylindrical projections, or centered
+    /// on a pole for azimuthal projections
+    Normal,
+    /// Rotated 90 degrees from the normal aspect: aligned with a meridian for cylindrical
+    /// projections, or centered on the equator for azimuthal projections
+    Transverse,
+    /// Any other orientation
+    Oblique,
+}
+
+/// Errors that can occur when projecting or unprojecting
+#[derive(Debug,Clone,PartialEq)]
+pub enum ProjectionError {
+    /// A latitude, longitude, or coordinate was NaN or infinite
+    InvalidInput,
+    /// The position is outside the region that the projection can represent
+    OutOfBounds,
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProjectionError::InvalidInput => write!(f, "Coordinate is not a finite number"),
+            ProjectionError::OutOfBounds => write!(f, "Position is outside the area that the projection can represent"),
+        }
+    }
+}
+
+impl Error for ProjectionError {}
+
+#[cfg(test)]
+mod tests {
+    use std::f64;
+    use super::*;
+    use equirectangular::EquirectangularProjection;
+    use stereographic::StereographicProjection;
+    use mercator::MercatorProjection;
+    use miller::MillerCylindricalProjection;
+    use ::close_enough;
+
+    #[test]
+    fn test_try_project_nan() {
+        let ll = LatLon { latitude: Latitude(f64::NAN), longitude: Longitude(10.0) };
+        assert_eq!(Err(ProjectionError::InvalidInput), EquirectangularProjection.try_project(&ll));
+    }
+    #[test]
+    fn test_try_project_infinite() {
+        let ll = LatLon { latitude: Latitude(10.0), longitude: Longitude(f64::INFINITY) };
+        assert_eq!(Err(ProjectionError::InvalidInput), EquirectangularProjection.try_project(&ll));
+    }
+    #[test]
+    fn test_try_unproject_nan_infinite() {
+        let nan = Point { x: f64::NAN, y: 0.0 };
+        let infinite = Point { x: 0.0, y: f64::NEG_INFINITY };
+        assert_eq!(Err(ProjectionError::InvalidInput), EquirectangularProjection.try_unproject(&nan));
+        assert_eq!(Err(ProjectionError::InvalidInput), EquirectangularProjection.try_unproject(&infinite));
+    }
+    #[test]
+    fn test_try_project_singularity() {
+        // The projection point of a stereographic projection projects to infinity
+        let projection_point = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        let stereo = StereographicProjection::new(projection_point.clone());
+        let projected = stereo.project(&projection_point);
+        assert!(!(projected.x.is_finite() && projected.y.is_finite()));
+        assert_eq!(Err(ProjectionError::OutOfBounds), stereo.try_project(&projection_point));
+        // The origin is the map center, which is a valid position
+        let center = stereo.try_unproject(&Point { x: 0.0, y: 0.0 }).unwrap();
+        assert!(center.approx_eq(&projection_point.antipode(), 1e-9));
+    }
+    #[test]
+    fn test_try_unproject_non_finite_result() {
+        struct Hemisphere;
+        impl Projection for Hemisphere {
+            fn project(&self, position: &LatLon) -> Point<f64> {
+                Point { x: position.longitude.0, y: position.latitude.0.to_radians().sin() }
+            }
+            fn unproject(&self, position: &Point<f64>) -> LatLon {
+                LatLon { latitude: Latitude(position.y.asin().to_degrees()), longitude: Longitude(position.x) }
+            }
+        }
+        assert!(Hemisphere.try_unproject(&Point { x: 10.0, y: 0.5 }).is_ok());
+        assert_eq!(Err(ProjectionError::OutOfBounds), Hemisphere.try_unproject(&Point { x: 10.0, y: 2.0 }));
+    }
+    #[test]
+    fn test_roundtrip_error_equirectangular() {
+        for latitude in (-8..9).map(|i| i as f64 * 10.0) {
+            for longitude in (-17..18).map(|i| i as f64 * 10.0) {
+                let ll = LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) };
+                assert!(EquirectangularProjection.roundtrip_error(&ll) < 1e-9);
+            }
+        }
+    }
+    #[test]
+    fn test_roundtrip_error_stereographic_singularity() {
+        // The projection point itself projects to infinity, and points next to it lose precision
+        let projection_point = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let stereo = StereographicProjection::new(projection_point.clone());
+        let near = LatLon { latitude: Latitude(47.6609 + 1e-9), longitude: Longitude(-122.2816) };
+        let far = projection_point.antipode();
+        assert!(stereo.roundtrip_error(&projection_point) > 1.0);
+        assert!(stereo.roundtrip_error(&near) > 1e-6);
+        assert!(stereo.roundtrip_error(&far) < 1e-9);
+    }
+    #[test]
+    fn test_natural_origin() {
+        let point = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
+        let stereo = StereographicProjection::new(point.clone());
+        assert_eq!(Some(point), stereo.natural_origin());
+        assert_eq!(None, EquirectangularProjection.natural_origin());
+    }
+    #[test]
+    fn test_aspect() {
+        let oblique = StereographicProjection::new(LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) });
+        let polar = StereographicProjection::new(LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) });
+        let equatorial = StereographicProjection::new(LatLon { latitude: Latitude(0.0), longitude: Longitude(30.0) });
+        assert_eq!(ProjectionAspect::Oblique, oblique.aspect());
+        assert_eq!(ProjectionAspect::Normal, polar.aspect());
+        assert_eq!(ProjectionAspect::Transverse, equatorial.aspect());
+        assert_eq!(ProjectionAspect::Normal, EquirectangularProjection.aspect());
+    }
+    #[test]
+    fn test_horizontal_period() {
+        let stereo = StereographicProjection::new(LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) });
+        let mercator = MercatorProjection::default();
+        assert_eq!(Some(360.0), EquirectangularProjection.horizontal_period());
+        assert_eq!(Some(360.0), MillerCylindricalProjection.horizontal_period());
+        assert!(close_enough(2.0 * f64::consts::PI * ::EARTH_MEAN_RADIUS, mercator.horizontal_period().unwrap()));
+        assert_eq!(None, stereo.horizontal_period());
+    }
+    #[test]
+    fn test_horizontal_period_matches_projection() {
+        // Moving one period east in map coordinates is a full turn around the earth
+        let mercator = MercatorProjection::default();
+        let position = LatLon { latitude: Latitude(30.0), longitude: Longitude(-170.0) };
+        let west = mercator.project(&position);
+        let east = mercator.project(&LatLon { longitude: Longitude(190.0), ..position });
+        assert!(close_enough(mercator.horizontal_period().unwrap(), east.x - west.x));
+    }
+    #[test]
+    fn test_reproject_bounds_edges_bow() {
+        // Viewed from above the north pole, parallels are circles, so the southern edge bows
+        // away from the pole between the corners
+        let polar = StereographicProjection::new(LatLon { latitude: Latitude(-90.0), longitude: Longitude(0.0) });
+        let rect = LatLonRect::from_bounds(Latitude(60.0), Latitude(30.0), Longitude(40.0), Longitude(-40.0));
+        let corners = reproject_bounds(&polar, &EquirectangularProjection, &rect, 1);
+        let edges = reproject_bounds(&polar, &EquirectangularProjection, &rect, 64);
+        assert!(edges.north() > corners.north());
+
+        // The bounds from the edges contain the rest of the edges
+        let dense = reproject_bounds(&polar, &EquirectangularProjection, &rect, 10_000);
+        assert!((dense.north().0 - edges.north().0).abs() < 1e-3);
+        assert!((dense.south().0 - edges.south().0).abs() < 1e-3);
+        assert!((dense.east().0 - edges.east().0).abs() < 1e-3);
+        assert!((dense.west().0 - edges.west().0).abs() < 1e-3);
+        // The point where the southern edge crosses the central meridian is the farthest from the pole
+        let farthest = EquirectangularProjection.unproject(&polar.project(&LatLon { latitude: Latitude(30.0), longitude: Longitude(0.0) }));
+        assert!(close_enough(farthest.latitude.0, edges.north().0));
+    }
+    #[test]
+    fn test_tissot_indicatrix_equirectangular() {
+        // At 60 degrees north, a degree of longitude is half as long as a degree of latitude,
+        // so the projection stretches the circle to twice its height
+        let center = LatLon { latitude: Latitude(60.0), longitude: Longitude(-20.0) };
+        let ellipse = tissot_indicatrix(&EquirectangularProjection, &center, 0.5, 72);
+        assert_eq!(72, ellipse.points().len());
+        let first = ellipse.points()[0].clone();
+        let (min, max) = ellipse.points().iter().fold((first.clone(), first), |(min, max), point| (min.min(point), max.max(point)));
+        let width = max.x - min.x;
+        let height = max.y - min.y;
+        assert!(width > height);
+        assert!((width / height - 2.0).abs() < 0.01);
+        assert!(close_enough(1.0, height));
+    }
+    #[test]
+    fn test_try_project_finite() {
+        let ll = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+        assert_eq!(Ok(Point { x: 20.0, y: 10.0 }), EquirectangularProjection.try_project(&ll));
     }
 }
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use projection::Projection;
+use equirectangular::EquirectangularProjection;
+use miller::MillerCylindricalProjection;
+use mercator::MercatorProjection;
+
+/// A function that creates a projection
+type Factory = Box<dyn Fn() -> Box<dyn Projection>>;
+
+///
+/// A collection of projections that can be created by name
+///
+/// A new registry contains the projections that do not need any parameters:
+/// "equirectangular", "miller", and "mercator" (on a sphere with the earth's mean radius,
+/// in meters). Other projections, including projections with parameters
+/// and projections defined outside this crate, can be added with register().
+///
+pub struct ProjectionRegistry {
+    factories: HashMap<String, Factory>,
+}
+
+impl ProjectionRegistry {
+    /// Creates a registry containing the built-in projections
+    pub fn new() -> ProjectionRegistry {
+        let mut registry = ProjectionRegistry::empty();
+        registry.register("equirectangular", || Box::new(EquirectangularProjection));
+        registry.register("miller", || Box::new(MillerCylindricalProjection));
+        registry.register("mercator", || Box::new(MercatorProjection::default()));
+        registry
+    }
+    /// Creates a registry with no projections
+    pub fn empty() -> ProjectionRegistry {
+        ProjectionRegistry {
+            factories: HashMap::new(),
+        }
+    }
+
+    ///
+    /// Registers a function that creates a projection with a name
+    ///
+    /// If a projection with the same name is already registered, it is replaced.
+    ///
+    pub fn register<F>(&mut self, name: &str, factory: F) where F: Fn() -> Box<dyn Projection> + 'static {
+        self.factories.insert(name.to_owned(), Box::new(factory));
+    }
+
+    /// Creates the projection registered with a name, or returns None if no projection has that name
+    pub fn create(&self, name: &str) -> Option<Box<dyn Projection>> {
+        self.factories.get(name).map(|factory| factory())
+    }
+
+    /// Returns true if a projection is registered with a name
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(name)
+    }
+}
+
+impl Default for ProjectionRegistry {
+    fn default() -> Self {
+        ProjectionRegistry::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mock::MockProjection;
+    use ::{Latitude, Longitude, LatLon, Point};
+
+    #[test]
+    fn test_built_in() {
+        let registry = ProjectionRegistry::new();
+        let projection = registry.create("equirectangular").unwrap();
+        let ll = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+        assert_eq!(Point { x: 20.0, y: 10.0 }, projection.project(&ll));
+        assert!(registry.contains("miller"));
+        assert!(registry.create("no such projection").is_none());
+        assert!(ProjectionRegistry::empty().create("equirectangular").is_none());
+    }
+    #[test]
+    fn test_register_custom() {
+        let mut registry = ProjectionRegistry::new();
+        registry.register("local-grid", || {
+            let mut mock = MockProjection::new();
+            mock.set_scale(3.0);
+            mock.set_offset(Point { x: 100.0, y: -50.0 });
+            Box::new(mock)
+        });
+        let projection = registry.create("local-grid").unwrap();
+        let ll = LatLon { latitude: Latitude(10.0), longitude: Longitude(20.0) };
+        assert_eq!(Point { x: 160.0, y: -20.0 }, projection.project(&ll));
+    }
+}
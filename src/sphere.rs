@@ -0,0 +1,106 @@
+use std::ops::{Add, Mul};
+
+use super::{Latitude, Longitude, LatLon};
+
+///
+/// A vector in earth-centered, earth-fixed coordinates on a unit sphere
+///
+/// The X axis points toward latitude 0, longitude 0, the Y axis points toward latitude 0,
+/// longitude 90, and the Z axis points toward the north pole.
+///
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub struct Vector3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vector3 {
+    /// Returns the unit vector pointing toward a latitude and longitude
+    pub fn from_latlon(position: &LatLon) -> Vector3 {
+        let latitude = position.latitude.to_radians();
+        let longitude = position.longitude.to_radians();
+        Vector3 {
+            x: latitude.cos() * longitude.cos(),
+            y: latitude.cos() * longitude.sin(),
+            z: latitude.sin(),
+        }
+    }
+
+    /// Returns the latitude and longitude that this vector points toward
+    ///
+    /// This vector does not need to be normalized.
+    pub fn to_latlon(self) -> LatLon {
+        let latitude = f64::atan2(self.z, f64::hypot(self.x, self.y));
+        let longitude = f64::atan2(self.y, self.x);
+        LatLon {
+            latitude: Latitude(latitude.to_degrees()),
+            longitude: Longitude(longitude.to_degrees()),
+        }
+    }
+
+    pub fn dot(self, other: Vector3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vector3) -> Vector3 {
+        Vector3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+
+    pub fn magnitude(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// Returns the angle between this vector and another vector, in radians
+    pub fn angle_to(self, other: Vector3) -> f64 {
+        f64::atan2(self.cross(other).magnitude(), self.dot(other))
+    }
+
+    /// Spherically interpolates between this unit vector and another unit vector
+    ///
+    /// t = 0 returns this vector and t = 1 returns the other vector. If the vectors are
+    /// parallel or opposite, the path between them is not defined and this returns this vector.
+    pub fn slerp(self, other: Vector3, t: f64) -> Vector3 {
+        let angle = self.angle_to(other);
+        let sin = angle.sin();
+        if sin.abs() < 1e-12 {
+            return self;
+        }
+        self * (((1.0 - t) * angle).sin() / sin) + other * ((t * angle).sin() / sin)
+    }
+
+    /// Rotates this vector around a unit axis by an angle in radians
+    ///
+    /// Positive angles rotate counterclockwise when looking from the tip of the axis
+    /// toward the origin.
+    pub fn rotate(self, axis: Vector3, radians: f64) -> Vector3 {
+        // Rodrigues' rotation formula
+        let (sin, cos) = radians.sin_cos();
+        self * cos + axis.cross(self) * sin + axis * (axis.dot(self) * (1.0 - cos))
+    }
+}
+
+impl Add for Vector3 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Vector3 {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+            z: self.z + rhs.z,
+        }
+    }
+}
+impl Mul<f64> for Vector3 {
+    type Output = Self;
+    fn mul(self, rhs: f64) -> Self {
+        Vector3 {
+            x: self.x * rhs,
+            y: self.y * rhs,
+            z: self.z * rhs,
+        }
+    }
+}
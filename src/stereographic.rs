@@ -1,5 +1,5 @@
-use super::{Latitude, Longitude, LatLon, Point, normalize_latitude, normalize_longitude};
-use projection::Projection;
+use super::{LatLon, Point};
+use projection::{Projection, ProjectionAspect, azimuthal_aspect, azimuthal_unproject};
 
 /// A stereographic projection around a projection point
 pub struct StereographicProjection {
@@ -10,7 +10,7 @@ pub struct StereographicProjection {
 impl StereographicProjection {
     pub fn new(projection_point: LatLon) -> StereographicProjection {
         StereographicProjection {
-            projection_point: projection_point,
+            projection_point,
         }
     }
 
@@ -26,85 +26,85 @@ impl StereographicProjection {
 
 impl Projection for StereographicProjection {
     fn project(&self, position: &LatLon) -> Point<f64> {
-        // Calculate a position relative to the projection point
-        let zenith_radians = (position.latitude - self.projection_point.latitude).to_radians();
-        let azimuth_radians = (position.longitude - self.projection_point.longitude).to_radians();
+        // The map is tangent to the sphere at the point opposite the projection point
+        let center = self.projection_point.antipode();
+        let center_lat = center.latitude.to_radians();
+        let lat = position.latitude.to_radians();
+        let delta_lon = (position.longitude - center.longitude).to_radians();
         // Project
-        let r = f64::sin(zenith_radians) / (1.0 - f64::cos(zenith_radians));
-        let theta = azimuth_radians;
-        // Convert to rectangular coordinates
-        let x = r * theta.cos();
-        let y = r * theta.sin();
+        let k = 2.0 / (1.0 + center_lat.sin() * lat.sin() + center_lat.cos() * lat.cos() * delta_lon.cos());
+        let x = k * lat.cos() * delta_lon.sin();
+        let y = k * (center_lat.cos() * lat.sin() - center_lat.sin() * lat.cos() * delta_lon.cos());
 
-        Point { x: x, y: y }
+        Point { x, y }
     }
     fn unproject(&self, position: &Point<f64>) -> LatLon {
-        // Convert to polar coordinates
-        let r = f64::hypot(position.x, position.y);
-        let theta = f64::atan2(position.y, position.x);
-        // Unproject
-        let zenith_radians = 2.0 * f64::atan(1.0 / r);
-        let azimuth_radians = theta;
-        // Convert to lat/lon
-        let latitude = normalize_latitude(Latitude(zenith_radians.to_degrees()) + self.projection_point.latitude);
-        let longitude = normalize_longitude(Longitude(azimuth_radians.to_degrees()) + self.projection_point.longitude);
-        LatLon {
-            latitude: latitude,
-            longitude: longitude,
-        }
+        // The projection point is opposite the center of the map
+        azimuthal_unproject(&self.projection_point.antipode(), position, |rho| 2.0 * f64::atan(rho / 2.0))
+    }
+
+    fn natural_origin(&self) -> Option<LatLon> {
+        Some(self.projection_point())
+    }
+    fn set_natural_origin(&mut self, origin: LatLon) -> bool {
+        self.set_projection_point(origin);
+        true
+    }
+    fn aspect(&self) -> ProjectionAspect {
+        azimuthal_aspect(&self.projection_point)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ::{LatLon, Point};
+    use ::{Latitude, Longitude, LatLon, Point};
 
     #[test]
     fn test_stereographic_identity_1() {
-        let center = LatLon { latitude: 0.0, longitude: 0.0 };
+        let center = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
         let stereo = StereographicProjection::new(center.clone());
         assert_eq!(center, stereo.projection_point());
 
-        let ll = LatLon { latitude: 47.6609, longitude: -122.2816 };
+        let ll = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
         let projected = stereo.project(&ll);
         let unprojected = stereo.unproject(&projected);
 
         println!("Stereographic: {:?} => {:?} => {:?}", ll, projected, unprojected);
-        assert_eq!(ll, unprojected);
+        assert!(ll.approx_eq(&unprojected, 1e-9));
     }
     #[test]
     fn test_stereographic_identity_2() {
-        let center = LatLon { latitude: 47.6609, longitude: -122.2816 };
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
         let stereo = StereographicProjection::new(center.clone());
         assert_eq!(center, stereo.projection_point());
 
-        let ll = LatLon { latitude: 37.4096, longitude: -122.299 };
+        let ll = LatLon { latitude: Latitude(37.4096), longitude: Longitude(-122.299) };
         let projected = stereo.project(&ll);
         let unprojected = stereo.unproject(&projected);
 
         println!("Stereographic: {:?} => {:?} => {:?}", ll, projected, unprojected);
-        assert_eq!(ll, unprojected);
+        assert!(ll.approx_eq(&unprojected, 1e-9));
     }
     #[test]
     fn test_stereographic_identity_3() {
-        let center = LatLon { latitude: 47.6609, longitude: -122.2816 };
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
         let stereo = StereographicProjection::new(center.clone());
         assert_eq!(center, stereo.projection_point());
 
-        let ll = LatLon { latitude: 37.4096, longitude: 122.299 };
+        let ll = LatLon { latitude: Latitude(37.4096), longitude: Longitude(122.299) };
         let projected = stereo.project(&ll);
         let unprojected = stereo.unproject(&projected);
 
         println!("Stereographic: {:?} => {:?} => {:?}", ll, projected, unprojected);
-        assert_eq!(ll, unprojected);
+        assert!(ll.approx_eq(&unprojected, 1e-9));
     }
     #[test]
     fn test_stereographic_antipode() {
-        let center = LatLon { latitude: 47.6609, longitude: -122.2816 };
+        let center = LatLon { latitude: Latitude(47.6609), longitude: Longitude(-122.2816) };
         let antipode = center.antipode();
         let stereo = StereographicProjection::new(center.clone());
         let projected = stereo.project(&antipode);
-        assert_eq!(projected, Point { x: 0.0, y: 0.0 });
+        assert!(projected.approx_eq(&Point::origin(), 1e-9));
     }
 }
@@ -0,0 +1,236 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use super::{Latitude, Longitude, LatLon};
+
+/// The characters that can separate degrees, minutes, and seconds
+const DMS_SYMBOLS: [char; 6] = ['°', 'º', '\'', '′', '"', '″'];
+
+/// Errors that can occur when parsing a LatLon from text
+#[derive(Debug,Clone,PartialEq)]
+pub enum ParseLatLonError {
+    /// The text is not a latitude and longitude in a supported format
+    InvalidFormat,
+    /// A number of minutes or seconds was not less than 60
+    InvalidMinutesOrSeconds,
+    /// The latitude is not in the range [-90, 90]
+    LatitudeOutOfRange,
+    /// The longitude is not in the range [-180, 180]
+    LongitudeOutOfRange,
+}
+
+impl fmt::Display for ParseLatLonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ParseLatLonError::InvalidFormat => write!(f, "Text is not a latitude and longitude in a supported format"),
+            ParseLatLonError::InvalidMinutesOrSeconds => write!(f, "Minutes and seconds must be less than 60"),
+            ParseLatLonError::LatitudeOutOfRange => write!(f, "Latitude is not between -90 and 90 degrees"),
+            ParseLatLonError::LongitudeOutOfRange => write!(f, "Longitude is not between -180 and 180 degrees"),
+        }
+    }
+}
+
+impl Error for ParseLatLonError {}
+
+///
+/// Parses a latitude and longitude
+///
+/// Two formats are supported:
+///
+/// * Decimal degrees, latitude first, separated by a comma or whitespace, with negative numbers
+///   for south and west: `47.66, -122.28`
+/// * Degrees, minutes, and seconds, each followed by a hemisphere letter (N, S, E, or W):
+///   `47°39'39"N 122°16'53"W`. Minutes and seconds are optional, and the latitude and longitude
+///   can be in either order.
+///
+impl FromStr for LatLon {
+    type Err = ParseLatLonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (latitude, longitude) = match parse_decimal(s) {
+            Some(pair) => pair,
+            None => parse_dms(s)?,
+        };
+        if !(-90.0..=90.0).contains(&latitude) {
+            return Err(ParseLatLonError::LatitudeOutOfRange);
+        }
+        if !(-180.0..=180.0).contains(&longitude) {
+            return Err(ParseLatLonError::LongitudeOutOfRange);
+        }
+        Ok(LatLon { latitude: Latitude(latitude), longitude: Longitude(longitude) })
+    }
+}
+
+impl LatLon {
+    ///
+    /// Formats this position in degrees, minutes, and seconds, like `47°39'39"N 122°16'53"W`
+    ///
+    /// Seconds are rounded to the nearest whole second, carrying into the minutes and degrees
+    /// when needed. Minutes and seconds have two digits. Zero latitude is north and zero
+    /// longitude is east, including small negative values that round to zero.
+    ///
+    pub fn to_dms_string(&self) -> String {
+        format!("{} {}", format_dms(self.latitude.0, 'N', 'S'), format_dms(self.longitude.0, 'E', 'W'))
+    }
+}
+
+/// Formats the same way as to_dms_string
+impl fmt::Display for LatLon {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_dms_string())
+    }
+}
+
+/// Formats an angle in degrees, minutes, and seconds with a hemisphere letter
+fn format_dms(degrees: f64, positive: char, negative: char) -> String {
+    let seconds = (degrees.abs() * 3600.0).round() as u64;
+    // Values that round to zero use the positive hemisphere whatever their sign
+    let hemisphere = if degrees < 0.0 && seconds != 0 { negative } else { positive };
+    format!("{}°{:02}'{:02}\"{}", seconds / 3600, seconds / 60 % 60, seconds % 60, hemisphere)
+}
+
+/// Parses two decimal numbers separated by a comma or whitespace
+fn parse_decimal(s: &str) -> Option<(f64, f64)> {
+    let parts: Vec<&str> = if s.contains(',') {
+        s.split(',').map(str::trim).collect()
+    } else {
+        s.split_whitespace().collect()
+    };
+    match parts[..] {
+        [latitude, longitude] => Some((latitude.parse().ok()?, longitude.parse().ok()?)),
+        _ => None,
+    }
+}
+
+/// Parses a latitude and a longitude in degrees, minutes, and seconds with hemisphere letters
+fn parse_dms(s: &str) -> Result<(f64, f64), ParseLatLonError> {
+    let mut latitude = None;
+    let mut longitude = None;
+    let mut rest = s;
+    while let Some(end) = rest.find(|c: char| c.is_alphabetic()) {
+        let hemisphere = rest[end..].chars().next().unwrap();
+        let magnitude = parse_dms_magnitude(rest[..end].trim_start_matches(|c: char| c == ',' || c.is_whitespace()))?;
+        let (value, sign) = match hemisphere.to_ascii_uppercase() {
+            'N' => (&mut latitude, 1.0),
+            'S' => (&mut latitude, -1.0),
+            'E' => (&mut longitude, 1.0),
+            'W' => (&mut longitude, -1.0),
+            _ => return Err(ParseLatLonError::InvalidFormat),
+        };
+        if value.is_some() {
+            return Err(ParseLatLonError::InvalidFormat);
+        }
+        *value = Some(sign * magnitude);
+        rest = &rest[end + hemisphere.len_utf8()..];
+    }
+    match (latitude, longitude) {
+        (Some(latitude), Some(longitude)) if rest.trim().is_empty() => Ok((latitude, longitude)),
+        _ => Err(ParseLatLonError::InvalidFormat),
+    }
+}
+
+/// Parses up to three numbers of degrees, minutes, and seconds, and returns the total in degrees
+fn parse_dms_magnitude(s: &str) -> Result<f64, ParseLatLonError> {
+    if s.chars().any(|c| !(c.is_ascii_digit() || c == '.' || c.is_whitespace() || DMS_SYMBOLS.contains(&c))) {
+        return Err(ParseLatLonError::InvalidFormat);
+    }
+    let components = s.split(|c: char| c.is_whitespace() || DMS_SYMBOLS.contains(&c))
+        .filter(|component| !component.is_empty())
+        .map(|component| component.parse::<f64>().map_err(|_| ParseLatLonError::InvalidFormat))
+        .collect::<Result<Vec<f64>, _>>()?;
+    if components.is_empty() || components.len() > 3 {
+        return Err(ParseLatLonError::InvalidFormat);
+    }
+    if components[1..].iter().any(|&component| component >= 60.0) {
+        return Err(ParseLatLonError::InvalidMinutesOrSeconds);
+    }
+    Ok(components.iter().zip([1.0, 60.0, 3600.0].iter()).map(|(component, divisor)| component / divisor).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::close_enough;
+
+    fn parse(s: &str) -> Result<LatLon, ParseLatLonError> {
+        s.parse()
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        let ll = parse("47.66, -122.28").unwrap();
+        assert_eq!(LatLon { latitude: Latitude(47.66), longitude: Longitude(-122.28) }, ll);
+        assert_eq!(ll, parse("47.66,-122.28").unwrap());
+        assert_eq!(ll, parse("  47.66   -122.28 ").unwrap());
+    }
+    #[test]
+    fn test_parse_decimal_negative() {
+        let ll = parse("-33.87, 151.21").unwrap();
+        assert_eq!(LatLon { latitude: Latitude(-33.87), longitude: Longitude(151.21) }, ll);
+        assert_eq!(LatLon { latitude: Latitude(-90.0), longitude: Longitude(-180.0) }, parse("-90,-180").unwrap());
+    }
+    #[test]
+    fn test_parse_dms() {
+        let ll = parse("47°39'39\"N 122°16'53\"W").unwrap();
+        assert!(close_enough(47.0 + 39.0 / 60.0 + 39.0 / 3600.0, ll.latitude.0));
+        assert!(close_enough(-(122.0 + 16.0 / 60.0 + 53.0 / 3600.0), ll.longitude.0));
+        // Longitude first, with a comma, and with typographic symbols
+        let reversed = parse("122°16′53″W, 47°39′39″N").unwrap();
+        assert_eq!(ll, reversed);
+    }
+    #[test]
+    fn test_parse_dms_southern_eastern() {
+        let ll = parse("33°52'S 151°12.5'E").unwrap();
+        assert!(close_enough(-(33.0 + 52.0 / 60.0), ll.latitude.0));
+        assert!(close_enough(151.0 + 12.5 / 60.0, ll.longitude.0));
+        let ll = parse("33.5s 151e").unwrap();
+        assert_eq!(LatLon { latitude: Latitude(-33.5), longitude: Longitude(151.0) }, ll);
+    }
+    #[test]
+    fn test_parse_malformed() {
+        for &text in ["", "47.66", "47.66, -122.28, 3", "north, west", "47°39'39\"N", "47°N 48°S",
+            "47°39'39\"N 122°16'53\"W extra", "-47°N 122°W", "47°39'39\"Q 122°W", "47;39 N 122 W"].iter() {
+            assert_eq!(Err(ParseLatLonError::InvalidFormat), parse(text), "{:?}", text);
+        }
+        assert_eq!(Err(ParseLatLonError::InvalidMinutesOrSeconds), parse("47°60'N 122°W"));
+        assert_eq!(Err(ParseLatLonError::InvalidMinutesOrSeconds), parse("47°59'75\"N 122°W"));
+    }
+    #[test]
+    fn test_format_dms() {
+        let ll = LatLon { latitude: Latitude(47.66083), longitude: Longitude(-122.28139) };
+        assert_eq!("47°39'39\"N 122°16'53\"W", ll.to_dms_string());
+        assert_eq!(ll.to_dms_string(), ll.to_string());
+        let ll = LatLon { latitude: Latitude(-33.0 - 5.0 / 3600.0), longitude: Longitude(151.2) };
+        assert_eq!("33°00'05\"S 151°12'00\"E", ll.to_dms_string());
+    }
+    #[test]
+    fn test_format_dms_zero() {
+        let ll = LatLon { latitude: Latitude(0.0), longitude: Longitude(0.0) };
+        assert_eq!("0°00'00\"N 0°00'00\"E", ll.to_dms_string());
+        let ll = LatLon { latitude: Latitude(-0.0), longitude: Longitude(-0.0) };
+        assert_eq!("0°00'00\"N 0°00'00\"E", ll.to_dms_string());
+        let ll = LatLon { latitude: Latitude(-0.0001), longitude: Longitude(-0.0001) };
+        assert_eq!("0°00'00\"N 0°00'00\"E", ll.to_dms_string());
+        let ll = LatLon { latitude: Latitude(-0.0002), longitude: Longitude(-0.0002) };
+        assert_eq!("0°00'01\"S 0°00'01\"W", ll.to_dms_string());
+    }
+    #[test]
+    fn test_format_dms_rounding() {
+        // 59.9 seconds rounds up to the next minute, and the minute to the next degree
+        let ll = LatLon { latitude: Latitude(10.0 + 59.0 / 60.0 + 59.9 / 3600.0), longitude: Longitude(-(20.0 + 4.0 / 60.0 + 59.7 / 3600.0)) };
+        assert_eq!("11°00'00\"N 20°05'00\"W", ll.to_dms_string());
+    }
+    #[test]
+    fn test_format_parse_round_trip() {
+        let ll = LatLon { latitude: Latitude(-12.5), longitude: Longitude(179.25) };
+        assert_eq!(ll, ll.to_dms_string().parse().unwrap());
+    }
+    #[test]
+    fn test_parse_out_of_range() {
+        assert_eq!(Err(ParseLatLonError::LatitudeOutOfRange), parse("91, 0"));
+        assert_eq!(Err(ParseLatLonError::LatitudeOutOfRange), parse("95°N 0°E"));
+        assert_eq!(Err(ParseLatLonError::LongitudeOutOfRange), parse("0, -180.5"));
+        assert_eq!(Err(ParseLatLonError::LatitudeOutOfRange), parse("NaN, 0"));
+    }
+}